@@ -58,7 +58,7 @@ fn insert_increasing_u64(b: &mut Bencher) {
     let mut i: u64 = 0;
     b.iter(|| {
         list.add(i);
-        i = i + 1
+        i += 1
     });
 }
 
@@ -68,7 +68,7 @@ fn insert_increasing_u64_BTreeMap(b: &mut Bencher) {
     let mut i: u64 = 0;
     b.iter(|| {
         list.insert(i, 0);
-        i = i + 1
+        i += 1
     })
 }
 
@@ -78,36 +78,36 @@ fn insert_increasing_u64_BTreeSet(b: &mut Bencher) {
     let mut i: u64 = 0;
     b.iter(|| {
         list.insert(i);
-        i = i + 1
+        i += 1
     })
 }
 
 #[bench]
 fn insert_decreasing_u64(b: &mut Bencher) {
     let mut list = SortedList::default();
-    let mut i: u64 = std::u64::MAX;
+    let mut i: u64 = u64::MAX;
     b.iter(|| {
         list.add(i);
-        i = i - 1
+        i -= 1
     });
 }
 
 #[bench]
 fn insert_decreasing_u64_BTreeMap(b: &mut Bencher) {
     let mut list = BTreeMap::new();
-    let mut i: u64 = std::u64::MAX;
+    let mut i: u64 = u64::MAX;
     b.iter(|| {
         list.insert(i, 0);
-        i = i - 1
+        i -= 1
     })
 }
 
 #[bench]
 fn insert_decreasing_u64_BTreeSet(b: &mut Bencher) {
     let mut list = BTreeSet::new();
-    let mut i: u64 = std::u64::MAX;
+    let mut i: u64 = u64::MAX;
     b.iter(|| {
         list.insert(i);
-        i = i - 1
+        i -= 1
     })
 }
@@ -59,7 +59,7 @@ fn push_sequential_u64(b: &mut Bencher) {
     let mut i: u64 = 0;
     b.iter(|| {
         list.push(i);
-        i = i + 1
+        i += 1
     });
 }
 
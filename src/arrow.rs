@@ -0,0 +1,98 @@
+//! Conversions between sorted lists and Apache Arrow arrays.
+//!
+//! Requires the `arrow` feature. A `PrimitiveArray` of integers converts into a `SortedList`
+//! with one copy into the sublists when it is already sorted and has no nulls, and is sorted
+//! first otherwise. `to_arrow` writes the list into a single contiguous array.
+//!
+//! Floating point arrays aren't supported, since their values aren't `Ord`.
+//!
+//! # Example usage
+//! ```
+//! extern crate arrow_array;
+//! extern crate sorted_collections;
+//!
+//! use arrow_array::types::Int64Type;
+//! use arrow_array::{Array, Int64Array};
+//! use sorted_collections::SortedList;
+//!
+//! let array = Int64Array::from(vec![Some(3), None, Some(1), Some(2)]);
+//! let list = SortedList::from(&array);
+//! assert!(list.iter().eq([1, 2, 3].iter()));
+//!
+//! let sorted = list.to_arrow::<Int64Type>();
+//! assert_eq!(3, sorted.len());
+//! assert_eq!(&[1, 2, 3], sorted.values().as_ref());
+//! ```
+
+use super::SortedList;
+use arrow_array::{Array, ArrowPrimitiveType, PrimitiveArray};
+
+impl<T: Ord + Copy> SortedList<T> {
+    /// Copies the list into a contiguous Arrow array, in order.
+    pub fn to_arrow<A: ArrowPrimitiveType<Native = T>>(&self) -> PrimitiveArray<A> {
+        PrimitiveArray::from_iter_values(self.iter().copied())
+    }
+}
+
+/// Collects the non-null values of an array.
+impl<'a, A> From<&'a PrimitiveArray<A>> for SortedList<A::Native>
+where
+    A: ArrowPrimitiveType,
+    A::Native: Ord,
+{
+    fn from(array: &'a PrimitiveArray<A>) -> Self {
+        let mut list = SortedList::new();
+        let values: &[A::Native] = array.values();
+        if array.null_count() == 0 && values.is_sorted() {
+            list.fill_sorted_from_slice(values);
+        } else {
+            let mut vals: Vec<A::Native> = array.iter().flatten().collect();
+            vals.sort_unstable();
+            list.fill_sorted(vals);
+        }
+        list
+    }
+}
+
+impl<A> From<PrimitiveArray<A>> for SortedList<A::Native>
+where
+    A: ArrowPrimitiveType,
+    A::Native: Ord,
+{
+    fn from(array: PrimitiveArray<A>) -> Self {
+        Self::from(&array)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::types::{Int32Type, UInt64Type};
+    use arrow_array::{Int32Array, UInt64Array};
+
+    #[test]
+    fn sorted_array() {
+        let array = UInt64Array::from_iter_values(0..5000);
+        let list = SortedList::from(&array);
+        assert_eq!(5000, list.len());
+        assert!(list.iter().cloned().eq(0..5000));
+        assert!(list.as_slices().all(|slice| slice.len() <= 1000));
+        assert_eq!(array, list.to_arrow::<UInt64Type>());
+    }
+
+    #[test]
+    fn unsorted_array_with_nulls() {
+        let array: Int32Array = (0..3000)
+            .map(|x| if x % 3 == 0 { None } else { Some(-x) })
+            .collect();
+        let list = SortedList::from(array);
+        assert_eq!(2000, list.len());
+        assert!(list.iter().is_sorted());
+        assert!(list.as_slices().all(|slice| slice.len() <= 1000));
+
+        let back = list.to_arrow::<Int32Type>();
+        assert_eq!(0, back.null_count());
+        assert_eq!(Some(&-2999), list.first());
+        assert_eq!(-2999, back.value(0));
+    }
+}
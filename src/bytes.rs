@@ -0,0 +1,185 @@
+//! Compact binary encoding for lists of plain-old-data values.
+//!
+//! Requires the `bytes` feature. A list is written as its length, as a little-endian `u64`,
+//! followed by the raw bytes of each element in order. On little-endian targets this is the same
+//! layout `bincode` uses by default for a `Vec` of primitives, so either side can be produced by
+//! `bincode` instead.
+//!
+//! Decoding a `SortedList` checks that the values are sorted but doesn't sort them, and builds
+//! the sublists directly, so loading a large index is a single copy.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::SortedList;
+//! let list: SortedList<u32> = vec![3, 1, 2].into_iter().collect();
+//! let bytes = list.to_bytes();
+//! assert_eq!(8 + 3 * 4, bytes.len());
+//!
+//! let decoded = SortedList::<u32>::from_bytes(&bytes).unwrap();
+//! assert!(decoded.iter().eq(list.iter()));
+//! ```
+
+use super::{SortedList, UnsortedList};
+use bytemuck::{cast_slice, cast_slice_mut, Pod};
+use std::error::Error;
+use std::fmt;
+use std::mem::size_of;
+
+/// The reasons decoding a list can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// There were fewer bytes than the header says there should be, or no header at all.
+    Truncated,
+    /// There were bytes left over after the last element.
+    TrailingBytes,
+    /// The values of a `SortedList` weren't in sorted order.
+    Unsorted,
+    /// A value was too large for the element type.
+    Overflow,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::Truncated => write!(f, "encoded list is truncated"),
+            DecodeError::TrailingBytes => write!(f, "encoded list has trailing bytes"),
+            DecodeError::Unsorted => write!(f, "encoded sorted list is not sorted"),
+            DecodeError::Overflow => write!(f, "encoded value is too large for the element type"),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+fn encode<'a, T: Pod, I: Iterator<Item = &'a [T]>>(len: usize, slices: I) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(8 + len * size_of::<T>());
+    bytes.extend_from_slice(&(len as u64).to_le_bytes());
+    for slice in slices {
+        bytes.extend_from_slice(cast_slice(slice));
+    }
+    bytes
+}
+
+fn decode<T: Pod>(bytes: &[u8]) -> Result<Vec<T>, DecodeError> {
+    if bytes.len() < 8 {
+        return Err(DecodeError::Truncated);
+    }
+    let (header, body) = bytes.split_at(8);
+    let mut len_bytes = [0; 8];
+    len_bytes.copy_from_slice(header);
+    let len = u64::from_le_bytes(len_bytes) as usize;
+
+    let body_len = len
+        .checked_mul(size_of::<T>())
+        .ok_or(DecodeError::Truncated)?;
+    if body.len() < body_len {
+        return Err(DecodeError::Truncated);
+    } else if body.len() > body_len {
+        return Err(DecodeError::TrailingBytes);
+    }
+
+    // Copying into a vector of `T` handles input that isn't aligned for `T`.
+    let mut vals = vec![T::zeroed(); len];
+    cast_slice_mut(&mut vals).copy_from_slice(body);
+    Ok(vals)
+}
+
+impl<T: Pod + Ord> SortedList<T> {
+    /// Encodes the list as its length followed by the raw bytes of its values.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode(self.len(), self.as_slices())
+    }
+
+    /// Decodes a list written by `to_bytes`, without re-sorting it.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let vals: Vec<T> = decode(bytes)?;
+        if !vals.is_sorted() {
+            return Err(DecodeError::Unsorted);
+        }
+        let mut list = SortedList::new();
+        list.fill_sorted(vals);
+        Ok(list)
+    }
+}
+
+impl<T: Pod + Ord> UnsortedList<T> {
+    /// Encodes the list as its length followed by the raw bytes of its values.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode(self.len(), self.as_slices())
+    }
+
+    /// Decodes a list written by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let vals: Vec<T> = decode(bytes)?;
+        Ok(vals.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let list: SortedList<i64> = (0..5000).map(|x| x * 3 - 100).collect();
+        let decoded = SortedList::<i64>::from_bytes(&list.to_bytes()).unwrap();
+        assert_eq!(5000, decoded.len());
+        assert!(decoded.iter().eq(list.iter()));
+
+        let unsorted: UnsortedList<u16> = (0..3000).rev().collect();
+        let decoded = UnsortedList::<u16>::from_bytes(&unsorted.to_bytes()).unwrap();
+        assert_eq!(3000, decoded.len());
+        assert!(decoded.iter().eq(unsorted.iter()));
+
+        let empty = SortedList::<u8>::new();
+        assert!(SortedList::<u8>::from_bytes(&empty.to_bytes())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn zero_sized_elements() {
+        let list: SortedList<()> = std::iter::repeat_n((), 3000).collect();
+        let bytes = list.to_bytes();
+        assert_eq!(8, bytes.len());
+        assert_eq!(3000, SortedList::<()>::from_bytes(&bytes).unwrap().len());
+    }
+
+    #[test]
+    fn unaligned_input() {
+        let list: SortedList<u64> = (0..10).collect();
+        let mut bytes = vec![0];
+        bytes.extend(list.to_bytes());
+        let decoded = SortedList::<u64>::from_bytes(&bytes[1..]).unwrap();
+        assert!(decoded.iter().eq(list.iter()));
+    }
+
+    #[test]
+    fn errors() {
+        let bytes = vec![1, 2]
+            .into_iter()
+            .collect::<SortedList<u32>>()
+            .to_bytes();
+        assert_eq!(
+            Err(DecodeError::Truncated),
+            SortedList::<u32>::from_bytes(&bytes[..9]).map(|_| ())
+        );
+        assert_eq!(
+            Err(DecodeError::Truncated),
+            SortedList::<u32>::from_bytes(&bytes[..3]).map(|_| ())
+        );
+        assert_eq!(
+            Err(DecodeError::TrailingBytes),
+            SortedList::<u16>::from_bytes(&bytes).map(|_| ())
+        );
+
+        let unsorted = vec![2, 1]
+            .into_iter()
+            .collect::<UnsortedList<u32>>()
+            .to_bytes();
+        assert_eq!(
+            Err(DecodeError::Unsorted),
+            SortedList::<u32>::from_bytes(&unsorted).map(|_| ())
+        );
+    }
+}
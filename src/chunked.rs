@@ -0,0 +1,832 @@
+//! Chunk management shared by the list types.
+//!
+//! Both `SortedList` and `UnsortedList` store their elements as a vector of sublists, each kept
+//! between half and double the load factor in length. `ChunkedStorage` owns the sublists and the
+//! operations that keep them that size, while the list types decide where elements go.
+//!
+//! Sizes are counted in elements rather than bytes, so zero-sized element types are chunked and
+//! indexed exactly like any other, and a list of `()` works as a counter.
+//!
+//! The invariants are:
+//! - There are no sublists until the first element is added, so empty storage can be created in
+//!   a `const` context without allocating. After that there is always at least one sublist, and
+//!   the only time a sublist is empty is when it is the only one.
+//! - `len` is the total length of the sublists.
+//! - Mutations through the methods here leave no sublist that the growth policy says to split.
+
+use super::growth::{GrowthPolicy, DEFAULT_POLICY};
+#[cfg(feature = "stats")]
+use super::stats::OpCounters;
+use super::Iter;
+use std::cmp::Ordering;
+use std::ops::Bound::{Excluded, Included, Unbounded};
+use std::ops::{Range, RangeBounds};
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub(crate) struct ChunkedStorage<T> {
+    pub(crate) lists: Vec<Vec<T>>,
+    // `None` means the default policy. The policy is shared with storage split off from this.
+    pub(crate) policy: Option<Arc<dyn GrowthPolicy>>,
+    pub(crate) len: usize,
+    // Bumped by the list types whenever their elements may have changed.
+    pub(crate) version: u64,
+    // Empty sublists kept by `clear_keep_capacity` or allocated by `reserve`, used by splits
+    // instead of allocating.
+    spare: Vec<Vec<T>>,
+    #[cfg(feature = "stats")]
+    pub(crate) counters: OpCounters,
+}
+
+impl<T> ChunkedStorage<T> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            lists: Vec::new(),
+            policy: None,
+            len: 0,
+            version: 0,
+            spare: Vec::new(),
+            #[cfg(feature = "stats")]
+            counters: OpCounters::new(),
+        }
+    }
+
+    /// Removes every element, leaving a single empty sublist. If `keep_capacity` is set, the
+    /// emptied sublists are kept for reuse as the storage grows again; otherwise they are freed,
+    /// along with any kept by an earlier call.
+    pub(crate) fn clear(&mut self, keep_capacity: bool) {
+        self.len = 0;
+        if keep_capacity {
+            let mut lists = self.lists.drain(..);
+            let first = lists.next();
+            for mut list in lists {
+                list.clear();
+                self.spare.push(list);
+            }
+            self.lists.extend(first);
+            if let Some(first) = self.lists.first_mut() {
+                first.clear();
+            }
+        } else {
+            self.lists.clear();
+            self.spare = Vec::new();
+        }
+        self.ensure_sublist();
+    }
+
+    pub(crate) fn bump_version(&mut self) {
+        self.version += 1;
+    }
+
+    pub(crate) fn with_policy(policy: Box<dyn GrowthPolicy>) -> Self {
+        Self::from_lists(Vec::new(), policy)
+    }
+
+    /// Wraps existing sublists. Unless they already satisfy the invariants, `normalize` must be
+    /// called before anything else.
+    pub(crate) fn from_lists(lists: Vec<Vec<T>>, policy: Box<dyn GrowthPolicy>) -> Self {
+        let mut chunks = Self::new();
+        chunks.len = lists.iter().map(Vec::len).sum();
+        chunks.lists = lists;
+        chunks.policy = Some(Arc::from(policy));
+        chunks
+    }
+
+    /// Moves the elements out into new storage with the same policy, leaving this empty.
+    pub(crate) fn take(&mut self) -> Self {
+        let mut taken = Self::new();
+        taken.lists = std::mem::take(&mut self.lists);
+        taken.len = std::mem::replace(&mut self.len, 0);
+        taken.policy = self.policy.clone();
+        taken
+    }
+
+    /// Moves everything from a (sublist, offset) position onwards into new storage with the same
+    /// policy. Only the sublist holding the position is split; later ones are moved whole.
+    pub(crate) fn split_off_indices(&mut self, (i, j): (usize, usize)) -> Self {
+        let mut tail = Self::new();
+        tail.policy = self.policy.clone();
+        if i < self.lists.len() {
+            let rest = self.lists.split_off(i + 1);
+            tail.lists.push(self.lists[i].split_off(j));
+            tail.lists.extend(rest);
+        }
+        self.normalize();
+        tail.normalize();
+        tail
+    }
+
+    /// Divides the sublists between `n` new storages with the same policy, in order, so that
+    /// each gets about the same number of elements. Sublists are moved whole, so some storages
+    /// may be empty if there are fewer than `n` sublists.
+    pub(crate) fn split_into(self, n: usize) -> Vec<Self> {
+        assert!(n > 0, "can't split a list into no parts");
+        let total = self.len;
+        let mut lists = self
+            .lists
+            .into_iter()
+            .filter(|list| !list.is_empty())
+            .peekable();
+        let mut parts = Vec::with_capacity(n);
+        let mut taken = 0;
+        for k in 0..n {
+            let target = total / n * (k + 1) + total % n * (k + 1) / n;
+            let mut part = Self::new();
+            part.policy = self.policy.clone();
+            // Take each sublist if that brings the part closer to its share than leaving it.
+            while let Some(list) =
+                lists.next_if(|list| k + 1 == n || taken + list.len() / 2 < target)
+            {
+                taken += list.len();
+                part.len += list.len();
+                part.lists.push(list);
+            }
+            parts.push(part);
+        }
+        parts
+    }
+
+    /// Adds the first sublist if there isn't one yet. Anything that inserts by position needs
+    /// to call this first.
+    pub(crate) fn ensure_sublist(&mut self) {
+        if self.lists.is_empty() {
+            self.lists.push(self.spare.pop().unwrap_or_default());
+        }
+    }
+
+    /// Allocates enough sublists up front for `additional` more elements, each with room to
+    /// grow to twice the policy's chunk size, which is when `LoadFactor` splits them. They are
+    /// kept as spares until splits need them.
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        let total = self.len.checked_add(additional).expect("capacity overflow");
+        let size = self.policy().chunk_size(total).max(1);
+        let needed = total.div_ceil(size).max(1);
+        let have = self.lists.len() + self.spare.len();
+        self.lists.reserve(needed.saturating_sub(self.lists.len()));
+        for _ in have..needed {
+            self.spare.push(Vec::with_capacity(2 * size));
+        }
+    }
+
+    /// The number of elements the sublists, including spare ones, have room for. Saturates
+    /// rather than overflowing, since a vector of a zero-sized type has a capacity of
+    /// `usize::MAX`.
+    pub(crate) fn capacity(&self) -> usize {
+        self.lists
+            .iter()
+            .chain(&self.spare)
+            .fold(0, |total, list| total.saturating_add(list.capacity()))
+    }
+
+    /// Frees the spare sublists and trims every sublist, and the vector holding them, to its
+    /// length.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.spare = Vec::new();
+        for list in &mut self.lists {
+            list.shrink_to_fit();
+        }
+        self.lists.shrink_to_fit();
+    }
+
+    pub(crate) fn policy(&self) -> &dyn GrowthPolicy {
+        self.policy.as_deref().unwrap_or(&DEFAULT_POLICY)
+    }
+
+    /// Whether a sublist of this length should be split. Sublists of one element never are.
+    pub(crate) fn should_split(&self, sublist_len: usize) -> bool {
+        sublist_len > 1 && self.policy().should_split(sublist_len, self.len)
+    }
+
+    pub(crate) fn should_merge(&self, sublist_len: usize) -> bool {
+        self.policy().should_merge(sublist_len, self.len)
+    }
+
+    pub(crate) fn chunk_size(&self) -> usize {
+        self.policy().chunk_size(self.len).max(1)
+    }
+
+    /// Counts sublist splits when the `stats` feature is enabled.
+    #[inline]
+    pub(crate) fn record_splits(&self, n: usize) {
+        #[cfg(feature = "stats")]
+        self.counters.add_splits(n);
+        #[cfg(not(feature = "stats"))]
+        let _ = n;
+    }
+
+    /// Counts sublist joins when the `stats` feature is enabled.
+    #[inline]
+    pub(crate) fn record_merges(&self, n: usize) {
+        #[cfg(feature = "stats")]
+        self.counters.add_merges(n);
+        #[cfg(not(feature = "stats"))]
+        let _ = n;
+    }
+
+    /// Counts elements shifted within a sublist when the `stats` feature is enabled.
+    #[inline]
+    pub(crate) fn record_shifts(&self, n: usize) {
+        #[cfg(feature = "stats")]
+        self.counters.add_shifts(n);
+        #[cfg(not(feature = "stats"))]
+        let _ = n;
+    }
+
+    /// Counts element comparisons when the `stats` feature is enabled.
+    #[inline]
+    pub(crate) fn record_comparisons(&self, n: usize) {
+        #[cfg(feature = "stats")]
+        self.counters.add_comparisons(n);
+        #[cfg(not(feature = "stats"))]
+        let _ = n;
+    }
+
+    pub(crate) fn iter(&self) -> Iter<'_, T> {
+        self.iter_from_indices((0, 0))
+    }
+
+    /// Iterates from a (sublist, offset) position to the end.
+    pub(crate) fn iter_from_indices(&self, (i, j): (usize, usize)) -> Iter<'_, T> {
+        match self.lists.get(i) {
+            Some(list) => Iter {
+                outer: self.lists[i + 1..].iter(),
+                inner: list[j..].iter(),
+            },
+            None => Iter {
+                outer: [].iter(),
+                inner: [].iter(),
+            },
+        }
+    }
+
+    /// The element at a (sublist, offset) position, if there is one.
+    pub(crate) fn get_indices(&self, (i, j): (usize, usize)) -> Option<&T> {
+        self.lists.get(i).and_then(|list| list.get(j))
+    }
+
+    pub(crate) fn first(&self) -> Option<&T> {
+        self.lists.first().and_then(|x| x.first())
+    }
+
+    pub(crate) fn first_mut(&mut self) -> Option<&mut T> {
+        self.lists.first_mut().and_then(|x| x.first_mut())
+    }
+
+    pub(crate) fn last(&self) -> Option<&T> {
+        self.lists.last().and_then(|x| x.last())
+    }
+
+    pub(crate) fn last_mut(&mut self) -> Option<&mut T> {
+        self.lists.last_mut().and_then(|x| x.last_mut())
+    }
+
+    /// The (sublist, offset) position of the element at index `i`, or `None` if it is out of
+    /// bounds.
+    pub(crate) fn position_indices(&self, mut i: usize) -> Option<(usize, usize)> {
+        for (list_i, list) in self.lists.iter().enumerate() {
+            if i < list.len() {
+                return Some((list_i, i));
+            }
+            i -= list.len();
+        }
+        None
+    }
+
+    /// The (sublist, offset) position of the element at index `i`, panicking if it is out of
+    /// bounds.
+    pub(crate) fn expect_position_indices(&self, i: usize) -> (usize, usize) {
+        match self.position_indices(i) {
+            Some(indices) => indices,
+            None => panic!("index {} out of range for list of length {}", i, self.len),
+        }
+    }
+
+    /// Resolves a range of indices into the whole list.
+    ///
+    /// Panics if the range is inverted or out of bounds, as slice indexing does.
+    pub(crate) fn index_range<R: RangeBounds<usize>>(&self, range: R) -> Range<usize> {
+        let start = match range.start_bound() {
+            Included(&i) => i,
+            Excluded(&i) => i + 1,
+            Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Included(&i) => i + 1,
+            Excluded(&i) => i,
+            Unbounded => self.len,
+        };
+        assert!(
+            start <= end,
+            "range starts at {} but ends at {}",
+            start,
+            end
+        );
+        assert!(
+            end <= self.len,
+            "range end {} out of range for list of length {}",
+            end,
+            self.len
+        );
+        start..end
+    }
+
+    /// The parts of the sublists that make up a range of indices, in order. Empty parts are
+    /// skipped.
+    pub(crate) fn slices_mut(
+        &mut self,
+        range: Range<usize>,
+    ) -> impl Iterator<Item = &mut [T]> + '_ {
+        let mut offset = 0;
+        self.lists.iter_mut().filter_map(move |list| {
+            let (list_start, list_end) = (offset, offset + list.len());
+            offset = list_end;
+            let (start, end) = (range.start.max(list_start), range.end.min(list_end));
+            if start < end {
+                Some(&mut list[start - list_start..end - list_start])
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The (sublist, offset) position of the element `k` places before the last one, found by
+    /// walking the sublists from the back, or `None` if it is out of bounds.
+    pub(crate) fn position_from_end_indices(&self, mut k: usize) -> Option<(usize, usize)> {
+        for (list_i, list) in self.lists.iter().enumerate().rev() {
+            if k < list.len() {
+                return Some((list_i, list.len() - 1 - k));
+            }
+            k -= list.len();
+        }
+        None
+    }
+
+    /// Converts a (sublist, offset) position into an index into the whole list.
+    pub(crate) fn flat_index(&self, i: usize, j: usize) -> usize {
+        self.lists[..i].iter().map(Vec::len).sum::<usize>() + j
+    }
+
+    /// The position one past the last element.
+    pub(crate) fn end_indices(&self) -> (usize, usize) {
+        match self.lists.last() {
+            Some(list) => (self.lists.len() - 1, list.len()),
+            None => (0, 0),
+        }
+    }
+
+    /// Removes the element at a (sublist, offset) position, joining sublists if needed.
+    pub(crate) fn remove_indices(&mut self, indices: (usize, usize)) -> T {
+        self.remove_and_contract(indices).0
+    }
+
+    /// Removes the element at a (sublist, offset) position as `remove_indices` does, also
+    /// returning whether sublists were joined. If they weren't, the elements after it have only
+    /// moved back by one within its sublist.
+    pub(crate) fn remove_and_contract(&mut self, (i, j): (usize, usize)) -> (T, bool) {
+        self.record_shifts(self.lists[i].len() - j - 1);
+        let rv = self.lists[i].remove(j);
+        self.len -= 1;
+        let joined = self.contract(i);
+        (rv, joined)
+    }
+
+    /// Removes the elements at a set of indices in one pass over the sublists, returning them in
+    /// index order. Repeated indices are removed once.
+    ///
+    /// Panics if any index is out of bounds.
+    pub(crate) fn remove_positions(&mut self, mut indices: Vec<usize>) -> Vec<T> {
+        indices.sort_unstable();
+        indices.dedup();
+        if let Some(&last) = indices.last() {
+            assert!(
+                last < self.len,
+                "index {} out of range for list of length {}",
+                last,
+                self.len
+            );
+        }
+
+        let mut removed = Vec::with_capacity(indices.len());
+        let mut indices = indices.into_iter().peekable();
+        let mut offset = 0;
+        for list in &mut self.lists {
+            let end = offset + list.len();
+            if indices.peek().is_some_and(|&i| i < end) {
+                let mut position = offset;
+                removed.extend(list.extract_if(.., |_| {
+                    position += 1;
+                    indices.next_if_eq(&(position - 1)).is_some()
+                }));
+            }
+            offset = end;
+        }
+        self.normalize();
+        removed
+    }
+
+    pub(crate) fn pop_first(&mut self) -> Option<T> {
+        if self.len == 0 {
+            None
+        } else {
+            Some(self.remove_indices((0, 0)))
+        }
+    }
+
+    pub(crate) fn pop_last(&mut self) -> Option<T> {
+        let rv = self.lists.last_mut().and_then(|l| l.pop())?;
+        self.len -= 1;
+        let last = self.lists.len() - 1;
+        self.contract(last);
+        Some(rv)
+    }
+
+    /// Splits sublist `i` if the policy says it is too long.
+    pub(crate) fn expand(&mut self, i: usize) {
+        if self.should_split(self.lists[i].len()) {
+            self.unchecked_expand(i)
+        }
+    }
+
+    /// Splits sublist `i` in half.
+    pub(crate) fn unchecked_expand(&mut self, i: usize) {
+        self.record_splits(1);
+        let new_list = {
+            let inner = &mut self.lists[i];
+            let mid = inner.len() / 2;
+            match self.spare.pop() {
+                Some(mut spare) => {
+                    spare.extend(inner.drain(mid..));
+                    spare
+                }
+                None => inner.split_off(mid),
+            }
+        };
+
+        self.lists.insert(i + 1, new_list);
+    }
+
+    /// Joins sublist `i` with a neighbour if the policy says it is too short, returning whether
+    /// it did.
+    pub(crate) fn contract(&mut self, i: usize) -> bool {
+        if self.lists.len() > 1 && self.should_merge(self.lists[i].len()) {
+            // Joining may make a sublist too big, in which case it is split again.
+            let joined = self.unchecked_contract(i);
+            self.expand(joined);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Contracts with the nearest list, returning the index of the joined list.
+    pub(crate) fn unchecked_contract(&mut self, i: usize) -> usize {
+        debug_assert!(self.lists.len() > 1);
+        let (low, high) = match i {
+            0 => (0, 1),
+            i if i == self.lists.len() - 1 => (self.lists.len() - 2, self.lists.len() - 1),
+            i => {
+                let other_list: usize = if self.lists[i - 1].len() < self.lists[i + 1].len() {
+                    i - 1
+                } else {
+                    i + 1
+                };
+                if i < other_list {
+                    (i, other_list)
+                } else {
+                    (other_list, i)
+                }
+            }
+        };
+
+        let mut removed_list = self.lists.remove(high);
+        self.lists[low].append(&mut removed_list);
+        self.record_merges(1);
+        low
+    }
+
+    /// Restores the sublist size invariants after a bulk change, and recounts the length.
+    ///
+    /// Empty sublists are dropped, undersized ones are joined with their successor, and oversized
+    /// ones are split.
+    pub(crate) fn normalize(&mut self) {
+        self.len = self.lists.iter().map(Vec::len).sum();
+        let old_lists = std::mem::take(&mut self.lists);
+        let policy = self.policy.as_deref().unwrap_or(&DEFAULT_POLICY);
+        let mut merges = 0;
+        for mut list in old_lists.into_iter().filter(|list| !list.is_empty()) {
+            match self.lists.last_mut() {
+                Some(last) if policy.should_merge(last.len(), self.len) => {
+                    last.append(&mut list);
+                    merges += 1;
+                }
+                _ => self.lists.push(list),
+            }
+        }
+
+        let n = self.lists.len();
+        if n > 1 && self.should_merge(self.lists[n - 1].len()) {
+            let mut tail = self.lists.pop().unwrap();
+            self.lists[n - 2].append(&mut tail);
+            merges += 1;
+        }
+        self.record_merges(merges);
+        if self.lists.is_empty() {
+            self.lists.push(Vec::new());
+        }
+
+        self.split_oversized();
+    }
+
+    /// Splits every sublist that the policy says is too long, until none are.
+    pub(crate) fn split_oversized(&mut self) {
+        let mut i = 0;
+        while i < self.lists.len() {
+            if self.should_split(self.lists[i].len()) {
+                self.unchecked_expand(i);
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    /// Replaces the contents with a vector, split into evenly sized sublists of at most the
+    /// policy's chunk size.
+    pub(crate) fn fill(&mut self, mut vals: Vec<T>) {
+        let lengths = self.chunk_lengths(vals.len());
+        self.lists.clear();
+        for &n in lengths.iter().rev().take(lengths.len() - 1) {
+            let at = vals.len() - n;
+            self.lists.push(vals.split_off(at));
+        }
+        self.lists.push(vals);
+        self.lists.reverse();
+    }
+
+    /// Replaces the contents with the merge of `runs`, each of which must already be sorted by
+    /// `compare`, split as in `fill`.
+    ///
+    /// Equal elements keep the order of their runs, so the result is stable if the runs were
+    /// sorted stably. Each run is freed as soon as it is used up, but until then the runs and the
+    /// new sublists are both held, so this can need up to `len` elements of extra memory.
+    pub(crate) fn fill_merged<F>(&mut self, runs: Vec<Vec<T>>, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let len = runs.iter().map(Vec::len).sum();
+        let mut rests: Vec<_> = runs.into_iter().map(Vec::into_iter).collect();
+        let mut heads: Vec<Option<T>> = rests.iter_mut().map(Iterator::next).collect();
+
+        // A min-heap of the runs that have elements left, ordered by their heads.
+        let mut heap: Vec<usize> = (0..heads.len()).filter(|&r| heads[r].is_some()).collect();
+        for i in (0..heap.len() / 2).rev() {
+            sift_down(&mut heap, i, &heads, &mut compare);
+        }
+
+        self.lists.clear();
+        for n in self.chunk_lengths(len) {
+            let mut list = Vec::with_capacity(n);
+            for _ in 0..n {
+                let r = heap[0];
+                let val = std::mem::replace(&mut heads[r], rests[r].next());
+                if heads[r].is_none() {
+                    rests[r] = Vec::new().into_iter();
+                    let last = heap.pop().unwrap();
+                    if !heap.is_empty() {
+                        heap[0] = last;
+                    }
+                }
+                sift_down(&mut heap, 0, &heads, &mut compare);
+                list.push(val.unwrap());
+            }
+            self.lists.push(list);
+        }
+    }
+
+    /// Replaces the contents with clones of a slice, split as in `fill`.
+    pub(crate) fn fill_from_slice(&mut self, vals: &[T])
+    where
+        T: Clone,
+    {
+        let mut rest = vals;
+        self.lists.clear();
+        for n in self.chunk_lengths(vals.len()) {
+            let (chunk, tail) = rest.split_at(n);
+            self.lists.push(chunk.to_vec());
+            rest = tail;
+        }
+    }
+
+    /// Rewrites the sublists to the evenly sized ones `fill` would make, one at a time so the
+    /// elements are never all in one vector. The new sublists have no spare capacity.
+    pub(crate) fn rebalance(&mut self) {
+        let lengths = self.chunk_lengths(self.len);
+        let mut vals = std::mem::take(&mut self.lists).into_iter().flatten();
+        for n in lengths {
+            let mut list = Vec::with_capacity(n);
+            list.extend(vals.by_ref().take(n));
+            self.lists.push(list);
+        }
+    }
+
+    /// How far the sublists are from the fewest that could hold the elements, as the fraction
+    /// of sublists that `rebalance` would remove. Zero means they are as full as they can be.
+    pub(crate) fn fragmentation(&self) -> f64 {
+        let actual = self.lists.iter().filter(|list| !list.is_empty()).count();
+        if actual == 0 {
+            return 0.0;
+        }
+        let ideal = self.len.div_ceil(self.chunk_size());
+        1.0 - ideal as f64 / actual as f64
+    }
+
+    /// Sets the length to `len` and returns the lengths of evenly sized sublists to hold that
+    /// many elements.
+    fn chunk_lengths(&mut self, len: usize) -> Vec<usize> {
+        self.len = len;
+        let n_lists = len.div_ceil(self.chunk_size()).max(1);
+        let (size, extra) = (len / n_lists, len % n_lists);
+        (0..n_lists)
+            .map(|i| size + usize::from(i < extra))
+            .collect()
+    }
+
+    /// Checks the invariants, for use in tests.
+    #[cfg(test)]
+    pub(crate) fn check(&self) -> bool {
+        (self.lists.len() <= 1 || self.lists.iter().all(|l| !l.is_empty()))
+            && self.lists.iter().all(|l| !self.should_split(l.len()))
+            && self.len == self.lists.iter().map(Vec::len).sum::<usize>()
+    }
+}
+
+/// Restores the heap order below position `i` of a min-heap of run indices, comparing runs by
+/// their heads and then by index, so that equal elements are taken from earlier runs first.
+fn sift_down<T, F>(heap: &mut [usize], mut i: usize, heads: &[Option<T>], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut before = |a: usize, b: usize| match (&heads[a], &heads[b]) {
+        (Some(x), Some(y)) => compare(x, y).then(a.cmp(&b)) == Ordering::Less,
+        _ => false,
+    };
+    loop {
+        let mut least = i;
+        for child in [2 * i + 1, 2 * i + 2] {
+            if child < heap.len() && before(heap[child], heap[least]) {
+                least = child;
+            }
+        }
+        if least == i {
+            return;
+        }
+        heap.swap(i, least);
+        i = least;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use growth::LoadFactor;
+
+    fn storage(load_factor: usize) -> ChunkedStorage<u32> {
+        ChunkedStorage::with_policy(Box::new(LoadFactor(load_factor)))
+    }
+
+    #[test]
+    fn positions() {
+        let mut chunks = storage(4);
+        chunks.fill((0..10).collect());
+        assert_eq!(
+            vec![4, 3, 3],
+            chunks.lists.iter().map(Vec::len).collect::<Vec<_>>()
+        );
+        assert_eq!(Some((1, 2)), chunks.position_indices(6));
+        assert_eq!(None, chunks.position_indices(10));
+        assert_eq!(6, chunks.flat_index(1, 2));
+        assert_eq!((2, 3), chunks.end_indices());
+        assert!(chunks.iter_from_indices((1, 2)).cloned().eq(6..10));
+        assert!(chunks.check());
+    }
+
+    #[test]
+    fn fill_from_slice() {
+        let (mut chunks, mut copied) = (storage(4), storage(4));
+        chunks.fill((0..10).collect());
+        copied.fill_from_slice(&(0..10).collect::<Vec<_>>());
+        assert_eq!(chunks.lists, copied.lists);
+        copied.fill_from_slice(&[]);
+        assert_eq!(vec![Vec::<u32>::new()], copied.lists);
+        assert!(copied.check());
+    }
+
+    #[test]
+    fn removals_contract() {
+        let mut chunks = storage(4);
+        chunks.fill((0..20).collect());
+        let mut expected: Vec<u32> = (0..20).collect();
+        while chunks.len > 0 {
+            let (first, last) = (chunks.pop_first(), chunks.pop_last());
+            assert_eq!(first, Some(expected.remove(0)));
+            assert_eq!(last, expected.pop());
+            if let Some((i, j)) = chunks.position_indices(expected.len() / 2) {
+                let mid = expected.len() / 2;
+                assert_eq!(expected.remove(mid), chunks.remove_indices((i, j)));
+            }
+            assert!(chunks.check());
+            assert!(chunks.iter().eq(expected.iter()));
+        }
+        assert_eq!(1, chunks.lists.len());
+        assert_eq!(None, chunks.pop_first());
+        assert_eq!(None, chunks.pop_last());
+    }
+
+    #[test]
+    fn contract_splits_oversized_merge() {
+        let mut chunks = ChunkedStorage::from_lists(
+            vec![vec![0, 1], vec![2, 3, 4, 5, 6, 7, 8]],
+            Box::new(LoadFactor(4)),
+        );
+        assert_eq!(0, chunks.remove_indices((0, 0)));
+        assert!(chunks.check());
+        assert!(chunks.iter().cloned().eq(1..9));
+    }
+
+    #[test]
+    fn clear_keeps_spare_sublists() {
+        let mut chunks = storage(4);
+        chunks.fill((0..20).collect());
+        let n_lists = chunks.lists.len();
+        chunks.clear(true);
+        assert!(chunks.check() && chunks.iter().next().is_none());
+        assert_eq!(n_lists - 1, chunks.spare.len());
+        assert!(chunks
+            .spare
+            .iter()
+            .all(|list| list.is_empty() && list.capacity() > 0));
+
+        chunks.lists[0].extend(0..8);
+        chunks.len = 8;
+        chunks.expand(0);
+        assert_eq!(n_lists - 2, chunks.spare.len());
+        assert!(chunks.iter().cloned().eq(0..8));
+
+        chunks.clear(false);
+        assert!(chunks.spare.is_empty() && chunks.check());
+    }
+
+    #[test]
+    fn reserve_allocates_spare_sublists() {
+        let mut chunks = storage(4);
+        chunks.reserve(20);
+        assert_eq!(5, chunks.spare.len());
+        assert!(chunks.lists.capacity() >= 5);
+        assert!(chunks.spare.iter().all(|list| list.capacity() >= 8));
+        chunks.reserve(10);
+        assert_eq!(5, chunks.spare.len());
+
+        chunks.ensure_sublist();
+        assert_eq!(4, chunks.spare.len());
+        assert!(chunks.lists[0].capacity() >= 8 && chunks.check());
+    }
+
+    #[test]
+    fn capacity_and_shrink_to_fit() {
+        let mut chunks = storage(4);
+        chunks.fill((0..20).collect());
+        chunks.reserve(20);
+        assert!(chunks.capacity() >= 40);
+        chunks.remove_positions((0..15).collect());
+        chunks.shrink_to_fit();
+        assert!(chunks.spare.is_empty() && chunks.check());
+        assert_eq!(5, chunks.capacity());
+        assert_eq!(chunks.lists.len(), chunks.lists.capacity());
+
+        let mut units = ChunkedStorage::<()>::new();
+        units.fill(vec![(); 3]);
+        assert_eq!(usize::MAX, units.capacity());
+    }
+
+    #[test]
+    fn normalize() {
+        let mut chunks = ChunkedStorage::from_lists(
+            vec![
+                vec![],
+                vec![1],
+                vec![2, 3, 4, 5, 6, 7, 8, 9, 10],
+                vec![],
+                vec![11],
+            ],
+            Box::new(LoadFactor(4)),
+        );
+        chunks.normalize();
+        assert!(chunks.check());
+        assert_eq!(11, chunks.len);
+        assert!(chunks.iter().cloned().eq(1..=11));
+
+        chunks.lists = vec![vec![], vec![]];
+        chunks.normalize();
+        assert!(chunks.check());
+        assert_eq!(0, chunks.len);
+    }
+}
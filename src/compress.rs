@@ -0,0 +1,318 @@
+//! A read-only sorted list whose chunks are kept LZ4-compressed in memory.
+//!
+//! Requires the `compress` feature. Archiving a list compresses each of its chunks separately and
+//! keeps the first and last value of each uncompressed, so a query only decompresses the chunks
+//! whose bounds it falls within. Chunks that are read often can be `warm`ed, which keeps them
+//! decompressed until the next `cool`.
+//!
+//! Values are returned by copy rather than by reference, since a cold chunk has nowhere to lend
+//! them from.
+//!
+//! # Scope
+//!
+//! This deliberately stops short of an archival mode for `SortedList` itself:
+//!
+//! - It is a separate type, because `SortedList` lends out `&T` and mutable sublists, which a
+//!   compressed chunk can't back.
+//! - It is read-only. To change the elements, `unarchive` the list, modify it and `archive` it
+//!   again.
+//! - Warming is manual rather than driven by access. Reads take `&self` and never change which
+//!   chunks are decompressed, so they need no interior mutability or locking and an archived
+//!   list can be shared across threads. Callers that know their hot ranges `warm` them, and
+//!   `cool` them again when memory matters more.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::SortedList;
+//! let list: SortedList<u64> = (0..10_000).map(|x| x / 4).collect();
+//! let mut archived = list.archive();
+//! assert!(archived.compressed_size() < 10_000 * 8);
+//!
+//! assert!(archived.contains(&1234));
+//! assert_eq!(4, archived.count(&1234));
+//! assert_eq!(Some(100), archived.get(400));
+//!
+//! archived.warm(1000..1100);
+//! assert_eq!(vec![1000; 4], archived.range(1000..1001));
+//! ```
+
+use super::sorted_utils::slice_range;
+use super::SortedList;
+use bytemuck::{cast_slice, cast_slice_mut, Pod};
+use std::ops::{Bound, RangeBounds};
+
+#[derive(Debug, Clone)]
+enum ChunkData<T> {
+    Hot(Vec<T>),
+    Cold(Vec<u8>),
+}
+
+#[derive(Debug, Clone)]
+struct ArchivedChunk<T> {
+    min: T,
+    max: T,
+    len: usize,
+    data: ChunkData<T>,
+}
+
+impl<T: Pod> ArchivedChunk<T> {
+    fn new(vals: &[T]) -> Self {
+        ArchivedChunk {
+            min: vals[0],
+            max: vals[vals.len() - 1],
+            len: vals.len(),
+            data: ChunkData::Cold(lz4_flex::compress(cast_slice(vals))),
+        }
+    }
+
+    fn decompress(bytes: &[u8], len: usize) -> Vec<T> {
+        let mut vals = vec![T::zeroed(); len];
+        // Only this module writes the compressed bytes, so they always decode to `len` values.
+        lz4_flex::decompress_into(bytes, cast_slice_mut(&mut vals))
+            .expect("archived chunk failed to decompress");
+        vals
+    }
+
+    /// Calls `f` with the chunk's values, decompressing them into a temporary if it is cold.
+    fn with_vals<R, F: FnOnce(&[T]) -> R>(&self, f: F) -> R {
+        match &self.data {
+            ChunkData::Hot(vals) => f(vals),
+            ChunkData::Cold(bytes) => f(&Self::decompress(bytes, self.len)),
+        }
+    }
+
+    fn into_vals(self) -> Vec<T> {
+        match self.data {
+            ChunkData::Hot(vals) => vals,
+            ChunkData::Cold(bytes) => Self::decompress(&bytes, self.len),
+        }
+    }
+
+    fn warm(&mut self) {
+        if let ChunkData::Cold(bytes) = &self.data {
+            self.data = ChunkData::Hot(Self::decompress(bytes, self.len));
+        }
+    }
+
+    fn cool(&mut self) {
+        if let ChunkData::Hot(vals) = &self.data {
+            self.data = ChunkData::Cold(lz4_flex::compress(cast_slice(vals)));
+        }
+    }
+}
+
+/// An immutable sorted list stored as separately compressed chunks.
+///
+/// Created by `SortedList::archive`. Chunks are only decompressed for good by `warm`; see the
+/// module documentation for why reads don't warm them automatically.
+#[derive(Debug, Clone)]
+pub struct ArchivedSortedList<T: Pod + Ord> {
+    chunks: Vec<ArchivedChunk<T>>,
+    len: usize,
+}
+
+impl<T: Pod + Ord> ArchivedSortedList<T> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn first(&self) -> Option<T> {
+        self.chunks.first().map(|chunk| chunk.min)
+    }
+
+    pub fn last(&self) -> Option<T> {
+        self.chunks.last().map(|chunk| chunk.max)
+    }
+
+    /// The element at position `i`, decompressing only the chunk that holds it.
+    pub fn get(&self, mut i: usize) -> Option<T> {
+        for chunk in &self.chunks {
+            if i < chunk.len {
+                return Some(chunk.with_vals(|vals| vals[i]));
+            }
+            i -= chunk.len;
+        }
+        None
+    }
+
+    pub fn contains(&self, val: &T) -> bool {
+        let i = self.chunks.partition_point(|chunk| chunk.max < *val);
+        match self.chunks.get(i) {
+            Some(chunk) if chunk.min <= *val => {
+                chunk.with_vals(|vals| vals.binary_search(val).is_ok())
+            }
+            _ => false,
+        }
+    }
+
+    /// The number of elements less than `val`.
+    pub fn rank(&self, val: &T) -> usize {
+        let i = self.chunks.partition_point(|chunk| chunk.max < *val);
+        let before: usize = self.chunks[..i].iter().map(|chunk| chunk.len).sum();
+        match self.chunks.get(i) {
+            Some(chunk) if chunk.min < *val => {
+                before + chunk.with_vals(|vals| vals.partition_point(|x| x < val))
+            }
+            _ => before,
+        }
+    }
+
+    /// The number of elements equal to `val`.
+    pub fn count(&self, val: &T) -> usize {
+        self.range(*val..=*val).len()
+    }
+
+    /// The elements within a range of values, decompressing only the chunks that overlap it.
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Vec<T> {
+        let mut result = Vec::new();
+        for chunk in self.overlapping(&range) {
+            chunk.with_vals(|vals| {
+                result.extend_from_slice(&vals[slice_range(vals, &range)]);
+            });
+        }
+        result
+    }
+
+    /// Iterates over every element, decompressing one chunk at a time as it goes.
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.chunks
+            .iter()
+            .flat_map(|chunk| chunk.with_vals(|vals| vals.to_vec()))
+    }
+
+    /// Keeps the chunks overlapping a range of values decompressed, so reads from them are as
+    /// cheap as from a `SortedList`.
+    pub fn warm<R: RangeBounds<T>>(&mut self, range: R) {
+        let (start, end) = self.overlapping_indices(&range);
+        for chunk in &mut self.chunks[start..end] {
+            chunk.warm();
+        }
+    }
+
+    /// Compresses every warmed chunk again.
+    pub fn cool(&mut self) {
+        for chunk in &mut self.chunks {
+            chunk.cool();
+        }
+    }
+
+    /// The number of chunks currently held decompressed.
+    pub fn warm_chunks(&self) -> usize {
+        self.chunks
+            .iter()
+            .filter(|chunk| matches!(chunk.data, ChunkData::Hot(_)))
+            .count()
+    }
+
+    /// The number of bytes of element data held, compressed or not. Excludes the per-chunk
+    /// bounds and bookkeeping.
+    pub fn compressed_size(&self) -> usize {
+        self.chunks
+            .iter()
+            .map(|chunk| match &chunk.data {
+                ChunkData::Hot(vals) => std::mem::size_of_val(vals.as_slice()),
+                ChunkData::Cold(bytes) => bytes.len(),
+            })
+            .sum()
+    }
+
+    /// Decompresses everything back into a `SortedList` that can be modified, keeping the
+    /// chunk boundaries.
+    pub fn unarchive(self) -> SortedList<T> {
+        SortedList::from_chunks_unchecked(
+            self.chunks
+                .into_iter()
+                .map(ArchivedChunk::into_vals)
+                .collect(),
+        )
+    }
+
+    fn overlapping<R: RangeBounds<T>>(&self, range: &R) -> &[ArchivedChunk<T>] {
+        let (start, end) = self.overlapping_indices(range);
+        &self.chunks[start..end]
+    }
+
+    /// The span of chunks whose bounds intersect `range`, found from the bounds alone.
+    fn overlapping_indices<R: RangeBounds<T>>(&self, range: &R) -> (usize, usize) {
+        let start = match range.start_bound() {
+            Bound::Included(x) => self.chunks.partition_point(|chunk| chunk.max < *x),
+            Bound::Excluded(x) => self.chunks.partition_point(|chunk| chunk.max <= *x),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(x) => self.chunks.partition_point(|chunk| chunk.min <= *x),
+            Bound::Excluded(x) => self.chunks.partition_point(|chunk| chunk.min < *x),
+            Bound::Unbounded => self.chunks.len(),
+        };
+        (start, end.max(start))
+    }
+}
+
+impl<T: Pod + Ord> SortedList<T> {
+    /// Compresses each chunk of the list for compact read-only storage.
+    pub fn archive(self) -> ArchivedSortedList<T> {
+        let len = self.len();
+        let chunks = self
+            .as_slices()
+            .filter(|vals| !vals.is_empty())
+            .map(ArchivedChunk::new)
+            .collect();
+        ArchivedSortedList { chunks, len }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    quickcheck! {
+        fn prop_matches_list(vals: Vec<u16>, probes: Vec<u16>) -> bool {
+            let list: SortedList<u16> = vals.into_iter().map(|x| x % 1000).collect();
+            let expected: Vec<u16> = list.iter().cloned().collect();
+            let mut archived = list.archive();
+            let mut ok = archived.iter().eq(expected.iter().cloned());
+            for (n, &probe) in probes.iter().enumerate() {
+                let probe = probe % 1000;
+                if n == probes.len() / 2 {
+                    archived.warm(probe..probe.saturating_add(100));
+                }
+                let lo = expected.partition_point(|&x| x < probe);
+                let hi = expected.partition_point(|&x| x <= probe.saturating_add(50));
+                ok &= archived.contains(&probe) == expected.contains(&probe)
+                    && archived.rank(&probe) == lo
+                    && archived.range(probe..=probe.saturating_add(50)) == expected[lo..hi]
+                    && archived.get(usize::from(probe)) == expected.get(usize::from(probe)).cloned();
+            }
+            archived.cool();
+            ok && archived.warm_chunks() == 0 && archived.unarchive().iter().eq(expected.iter())
+        }
+    }
+
+    #[test]
+    fn warm_and_cool() {
+        let list: SortedList<u32> = (0..20_000).map(|x| x / 8).collect();
+        let n_chunks = list.as_slices().count();
+        let mut archived = list.archive();
+        assert!(n_chunks > 2);
+        assert_eq!(0, archived.warm_chunks());
+        let cold_size = archived.compressed_size();
+        assert!(cold_size < 20_000 * 4);
+
+        archived.warm(..=0);
+        assert_eq!(1, archived.warm_chunks());
+        assert!(archived.compressed_size() > cold_size);
+        archived.warm(..);
+        assert_eq!(n_chunks, archived.warm_chunks());
+        archived.cool();
+        assert_eq!(cold_size, archived.compressed_size());
+
+        assert_eq!(Some(0), archived.first());
+        assert_eq!(Some(2499), archived.last());
+        assert_eq!(None, archived.get(20_000));
+        assert!(SortedList::<u32>::new().archive().is_empty());
+    }
+}
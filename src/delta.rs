@@ -0,0 +1,263 @@
+//! Compact encoding for sorted lists of unsigned integers.
+//!
+//! Requires the `delta` feature. Each value is written as its difference from the one before,
+//! as a LEB128 varint, so dense lists of large ids take a byte or two per element rather than
+//! their full width.
+//!
+//! The encoding is split into the list's chunks. It starts with the number of chunks, and each
+//! chunk is written as its element count, its length in bytes, its first value in full, and then
+//! the differences. Chunks don't depend on each other, so they can be located from the lengths
+//! and decoded in parallel, which `par_from_delta_bytes` does with the `rayon` feature.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::SortedList;
+//! let list: SortedList<u64> = (0..1000).map(|x| 1_000_000_000 + x * 3).collect();
+//! let bytes = list.to_delta_bytes();
+//! assert!(bytes.len() < 1100);
+//!
+//! let decoded = SortedList::<u64>::from_delta_bytes(&bytes).unwrap();
+//! assert!(decoded.iter().eq(list.iter()));
+//! ```
+
+use super::bytes::DecodeError;
+use super::SortedList;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// An unsigned integer type that can be delta encoded.
+pub trait DeltaKey: Ord + Copy {
+    fn to_u64(self) -> u64;
+    /// Converts back, or returns `None` if the value doesn't fit.
+    fn from_u64(val: u64) -> Option<Self>;
+}
+
+macro_rules! impl_delta_key {
+    ($($t:ty)*) => {
+        $(
+            impl DeltaKey for $t {
+                fn to_u64(self) -> u64 {
+                    self as u64
+                }
+
+                fn from_u64(val: u64) -> Option<Self> {
+                    if val <= <$t>::MAX as u64 {
+                        Some(val as $t)
+                    } else {
+                        None
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_delta_key!(u8 u16 u32 u64 usize);
+
+fn write_varint(bytes: &mut Vec<u8>, mut val: u64) {
+    while val >= 0x80 {
+        bytes.push(val as u8 | 0x80);
+        val >>= 7;
+    }
+    bytes.push(val as u8);
+}
+
+/// Reads a varint from the front of `bytes`, advancing past it.
+fn read_varint(bytes: &mut &[u8]) -> Result<u64, DecodeError> {
+    let mut val = 0u64;
+    for (i, &byte) in bytes.iter().enumerate() {
+        let shift = 7 * i as u32;
+        if shift >= 64 || (shift == 63 && byte > 1) {
+            return Err(DecodeError::Overflow);
+        }
+        val |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            *bytes = &bytes[i + 1..];
+            return Ok(val);
+        }
+    }
+    Err(DecodeError::Truncated)
+}
+
+/// Decodes one chunk's values from exactly the bytes of its body.
+fn decode_chunk<T: DeltaKey>(mut body: &[u8], count: usize) -> Result<Vec<T>, DecodeError> {
+    let mut vals = Vec::with_capacity(count.min(body.len()));
+    let mut prev = 0u64;
+    for i in 0..count {
+        let delta = read_varint(&mut body)?;
+        prev = if i == 0 {
+            delta
+        } else {
+            prev.checked_add(delta).ok_or(DecodeError::Overflow)?
+        };
+        vals.push(T::from_u64(prev).ok_or(DecodeError::Overflow)?);
+    }
+    if !body.is_empty() {
+        return Err(DecodeError::TrailingBytes);
+    }
+    Ok(vals)
+}
+
+impl<T: DeltaKey> SortedList<T> {
+    /// Encodes the list as chunks of varint differences between consecutive values.
+    pub fn to_delta_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.len() + 8);
+        write_varint(&mut bytes, self.as_slices().count() as u64);
+        let mut body = Vec::new();
+        for slice in self.as_slices() {
+            body.clear();
+            let mut prev = 0;
+            for x in slice.iter().map(|x| x.to_u64()) {
+                write_varint(&mut body, x - prev);
+                prev = x;
+            }
+            write_varint(&mut bytes, slice.len() as u64);
+            write_varint(&mut bytes, body.len() as u64);
+            bytes.extend_from_slice(&body);
+        }
+        bytes
+    }
+
+    /// Decodes a list written by `to_delta_bytes`.
+    pub fn from_delta_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let chunks = locate_chunks(bytes)?
+            .into_iter()
+            .map(|(body, count)| decode_chunk(body, count))
+            .collect::<Result<Vec<Vec<T>>, _>>()?;
+        check_chunk_order(&chunks)?;
+        Ok(SortedList::from_chunks_unchecked(chunks))
+    }
+
+    /// Decodes a list written by `to_delta_bytes`, decoding its chunks in parallel on the rayon
+    /// thread pool. Requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn par_from_delta_bytes(bytes: &[u8]) -> Result<Self, DecodeError>
+    where
+        T: Send,
+    {
+        let chunks = locate_chunks(bytes)?
+            .into_par_iter()
+            .map(|(body, count)| decode_chunk(body, count))
+            .collect::<Result<Vec<Vec<T>>, _>>()?;
+        check_chunk_order(&chunks)?;
+        Ok(SortedList::from_chunks_unchecked(chunks))
+    }
+}
+
+/// Splits an encoding into the bodies of its chunks and their element counts, without decoding
+/// them.
+fn locate_chunks(mut bytes: &[u8]) -> Result<Vec<(&[u8], usize)>, DecodeError> {
+    let n_chunks = read_varint(&mut bytes)?;
+    let mut chunks = Vec::new();
+    for _ in 0..n_chunks {
+        let count = read_varint(&mut bytes)? as usize;
+        let body_len = read_varint(&mut bytes)? as usize;
+        if bytes.len() < body_len {
+            return Err(DecodeError::Truncated);
+        }
+        let (body, rest) = bytes.split_at(body_len);
+        bytes = rest;
+        chunks.push((body, count));
+    }
+    if !bytes.is_empty() {
+        return Err(DecodeError::TrailingBytes);
+    }
+    Ok(chunks)
+}
+
+/// Checks that each chunk starts no lower than the last value before it. Empty chunks are
+/// skipped over, so the comparison is with the most recent non-empty chunk.
+fn check_chunk_order<T: Ord>(chunks: &[Vec<T>]) -> Result<(), DecodeError> {
+    let mut prev_last = None;
+    for chunk in chunks {
+        if let (Some(prev_last), Some(first)) = (prev_last, chunk.first()) {
+            if prev_last > first {
+                return Err(DecodeError::Unsorted);
+            }
+        }
+        prev_last = chunk.last().or(prev_last);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    quickcheck! {
+        fn prop_round_trip(vals: Vec<u64>) -> bool {
+            let list: SortedList<u64> = vals.into_iter().collect();
+            let decoded = SortedList::<u64>::from_delta_bytes(&list.to_delta_bytes()).unwrap();
+            decoded.iter().eq(list.iter())
+        }
+    }
+
+    #[test]
+    fn chunked_and_compact() {
+        let list: SortedList<u32> = (0..5000).map(|x| x * 2).collect();
+        let bytes = list.to_delta_bytes();
+        assert!(bytes.len() < 5000 + 100);
+        assert_eq!(list.as_slices().count() as u8, bytes[0]);
+        let decoded = SortedList::<u32>::from_delta_bytes(&bytes).unwrap();
+        assert!(decoded.iter().eq(list.iter()));
+        assert_eq!(list.as_slices().count(), decoded.as_slices().count());
+
+        let empty = SortedList::<u8>::new().to_delta_bytes();
+        assert!(SortedList::<u8>::from_delta_bytes(&empty)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn errors() {
+        let bytes = vec![1u64, 300]
+            .into_iter()
+            .collect::<SortedList<_>>()
+            .to_delta_bytes();
+        assert_eq!(
+            Err(DecodeError::Truncated),
+            SortedList::<u64>::from_delta_bytes(&bytes[..bytes.len() - 1]).map(|_| ())
+        );
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(
+            Err(DecodeError::TrailingBytes),
+            SortedList::<u64>::from_delta_bytes(&trailing).map(|_| ())
+        );
+        assert_eq!(
+            Err(DecodeError::Overflow),
+            SortedList::<u8>::from_delta_bytes(&bytes).map(|_| ())
+        );
+        assert_eq!(
+            Err(DecodeError::Overflow),
+            SortedList::<u64>::from_delta_bytes(&[0xff; 11]).map(|_| ())
+        );
+
+        // Two chunks of one value each, the second smaller than the first.
+        let unsorted = [2, 1, 1, 5, 1, 1, 3];
+        assert_eq!(
+            Err(DecodeError::Unsorted),
+            SortedList::<u64>::from_delta_bytes(&unsorted).map(|_| ())
+        );
+        // The same, but with an empty chunk between them.
+        let unsorted = [3, 1, 1, 5, 0, 0, 1, 1, 1];
+        assert_eq!(
+            Err(DecodeError::Unsorted),
+            SortedList::<u64>::from_delta_bytes(&unsorted).map(|_| ())
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_decode() {
+        let list: SortedList<u32> = (0..5000).map(|x| x * 2).collect();
+        let decoded = SortedList::<u32>::par_from_delta_bytes(&list.to_delta_bytes()).unwrap();
+        assert!(decoded.iter().eq(list.iter()));
+
+        let unsorted = [3, 1, 1, 5, 0, 0, 1, 1, 1];
+        assert_eq!(
+            Err(DecodeError::Unsorted),
+            SortedList::<u64>::par_from_delta_bytes(&unsorted).map(|_| ())
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! Policies deciding when sublists are split and joined.
+//!
+//! Lists store their elements in sublists, and a `GrowthPolicy` decides how long those sublists
+//! may get. Longer sublists mean fewer of them to search but more shifting on each insertion.
+//! The default, `LoadFactor`, keeps sublists between half and double a fixed length.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::{GrowthPolicy, SortedList};
+//!
+//! /// Splits eagerly but only joins nearly empty sublists.
+//! #[derive(Debug)]
+//! struct Lopsided;
+//!
+//! impl GrowthPolicy for Lopsided {
+//!     fn should_split(&self, sublist_len: usize, _total_len: usize) -> bool {
+//!         sublist_len >= 64
+//!     }
+//!
+//!     fn should_merge(&self, sublist_len: usize, _total_len: usize) -> bool {
+//!         sublist_len < 4
+//!     }
+//!
+//!     fn chunk_size(&self, _total_len: usize) -> usize {
+//!         48
+//!     }
+//! }
+//!
+//! let mut list = SortedList::with_growth_policy(Lopsided);
+//! for x in 0..1000 {
+//!     list.add(x);
+//! }
+//! assert_eq!(1000, list.len());
+//! assert!(list.as_slices().all(|slice| slice.len() < 64));
+//! ```
+
+use super::sorted_utils::DEFAULT_LOAD_FACTOR;
+use std::fmt::Debug;
+
+/// Decides when a sublist is split or joined with a neighbour, and how long sublists built in
+/// bulk are.
+///
+/// Every method is given the length of the whole list, so the policy can depend on it. A
+/// sublist of `chunk_size` elements should need neither splitting nor merging, and the halves of
+/// a sublist that needs splitting shouldn't need merging, or lists will keep reorganizing
+/// themselves.
+pub trait GrowthPolicy: Debug + Send + Sync {
+    /// Whether a sublist has grown long enough to be split in half.
+    fn should_split(&self, sublist_len: usize, total_len: usize) -> bool;
+
+    /// Whether a sublist has shrunk enough to be joined with a neighbour.
+    fn should_merge(&self, sublist_len: usize, total_len: usize) -> bool;
+
+    /// The length of each sublist when a list is built from many values at once.
+    fn chunk_size(&self, total_len: usize) -> usize;
+}
+
+/// The policy used by lists that weren't given one.
+pub(crate) static DEFAULT_POLICY: LoadFactor = LoadFactor(DEFAULT_LOAD_FACTOR);
+
+/// Keeps sublists between half and double a fixed load factor.
+///
+/// This is the default policy, with a load factor of 1000.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadFactor(pub usize);
+
+impl Default for LoadFactor {
+    fn default() -> Self {
+        DEFAULT_POLICY
+    }
+}
+
+impl GrowthPolicy for LoadFactor {
+    fn should_split(&self, sublist_len: usize, _total_len: usize) -> bool {
+        // >= because otherwise contract can fail... better solution for this?
+        sublist_len >= 2 * self.0
+    }
+
+    fn should_merge(&self, sublist_len: usize, _total_len: usize) -> bool {
+        sublist_len < self.0 / 2
+    }
+
+    fn chunk_size(&self, _total_len: usize) -> usize {
+        self.0
+    }
+}
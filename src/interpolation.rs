@@ -0,0 +1,80 @@
+//! Interpolation search for elements with a numeric projection.
+//!
+//! When keys are roughly uniformly distributed, guessing a position from the key's value
+//! takes far fewer comparisons than bisecting. Probes alternate between interpolation and
+//! bisection so badly distributed keys still take `O(log n)` comparisons.
+//!
+//! Interpolation is opt-in: it is only used by `SortedList::interpolation_search`. The other
+//! searches (`bisect_left`, `contains`, `range` and so on) always bisect, even when `T`
+//! implements `InterpolatableKey`, because choosing a search per element type would need
+//! specialization, which stable Rust doesn't have.
+
+/// A type that can be projected onto a number line in a way consistent with its ordering.
+///
+/// If `a < b` then `a.key() <= b.key()` must hold. The projection may be lossy (as for large
+/// integers), since it is only used to choose where to look; comparisons still use `Ord`.
+pub trait InterpolatableKey {
+    fn key(&self) -> f64;
+}
+
+macro_rules! impl_interpolatable_key {
+    ($($t:ty)*) => {
+        $(
+            impl InterpolatableKey for $t {
+                fn key(&self) -> f64 {
+                    *self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_interpolatable_key!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize f32 f64);
+
+/// Finds the first element of `slice` that doesn't satisfy `pred`, as `slice::partition_point`,
+/// using `key` to interpolate the probe positions towards `target`.
+pub fn interpolation_partition_point<E, K, P>(slice: &[E], target: f64, key: K, pred: P) -> usize
+where
+    K: Fn(&E) -> f64,
+    P: Fn(&E) -> bool,
+{
+    let (mut lo, mut hi) = (0, slice.len());
+    let mut interpolate = true;
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let probe = if interpolate {
+            let (low_key, high_key) = (key(&slice[lo]), key(&slice[hi - 1]));
+            if high_key > low_key {
+                let fraction = (target - low_key) / (high_key - low_key);
+                let offset = (fraction * (hi - 1 - lo) as f64).max(0.0) as usize;
+                lo + offset.min(hi - 1 - lo)
+            } else {
+                mid
+            }
+        } else {
+            mid
+        };
+        interpolate = !interpolate;
+
+        if pred(&slice[probe]) {
+            lo = probe + 1;
+        } else {
+            hi = probe;
+        }
+    }
+    lo
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    quickcheck! {
+        fn prop_matches_partition_point(vals: Vec<i32>, target: i32) -> bool {
+            let mut vals = vals;
+            vals.sort();
+            let expected = vals.partition_point(|x| *x < target);
+            expected == interpolation_partition_point(&vals, target.key(), |x| x.key(), |x| *x < target)
+        }
+    }
+}
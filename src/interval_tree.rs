@@ -0,0 +1,193 @@
+//! A collection of possibly overlapping intervals, supporting stabbing and overlap queries.
+//!
+//! Intervals are kept in a sorted list ordered by their start, and each sublist tracks the
+//! greatest end of the intervals in it. Queries skip any sublist whose intervals all end too
+//! early, and stop at the first sublist whose intervals all start too late.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::IntervalTree;
+//! let mut tree = IntervalTree::new();
+//! tree.insert(0..10, "a");
+//! tree.insert(5..15, "b");
+//! tree.insert(20..30, "c");
+//!
+//! let at_7: Vec<_> = tree.query_point(&7).map(|(_, v)| *v).collect();
+//! assert_eq!(vec!["a", "b"], at_7);
+//!
+//! let overlapping: Vec<_> = tree.query_overlapping(12..25).map(|(_, v)| *v).collect();
+//! assert_eq!(vec!["b", "c"], overlapping);
+//! ```
+
+use super::sorted_list::aggregate::{Aggregate, AggregatedList};
+use std::cmp::Ordering;
+use std::ops::Range;
+
+/// An interval and its value. Ordered by the interval's start, then its end.
+#[derive(Debug)]
+struct Interval<T, V> {
+    range: Range<T>,
+    value: V,
+}
+
+impl<T: Ord, V> PartialEq for Interval<T, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Ord, V> Eq for Interval<T, V> {}
+
+impl<T: Ord, V> PartialOrd for Interval<T, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord, V> Ord for Interval<T, V> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.range.start, &self.range.end).cmp(&(&other.range.start, &other.range.end))
+    }
+}
+
+/// The greatest end of a run of intervals.
+#[derive(Debug)]
+struct MaxEnd;
+
+impl<T: Ord + Clone, V> Aggregate<Interval<T, V>> for MaxEnd {
+    type Summary = Option<T>;
+
+    fn empty() -> Option<T> {
+        None
+    }
+
+    fn single(interval: &Interval<T, V>) -> Option<T> {
+        Some(interval.range.end.clone())
+    }
+
+    fn combine(left: &Option<T>, right: &Option<T>) -> Option<T> {
+        std::cmp::max(left, right).clone()
+    }
+}
+
+/// A collection of half-open intervals with associated values.
+///
+/// Overlapping and identical intervals are all kept. Empty intervals are stored but never match
+/// a query.
+#[derive(Debug)]
+pub struct IntervalTree<T: Ord + Clone, V> {
+    intervals: AggregatedList<Interval<T, V>, MaxEnd>,
+}
+
+impl<T: Ord + Clone, V> IntervalTree<T, V> {
+    pub fn new() -> Self {
+        Self {
+            intervals: AggregatedList::new(),
+        }
+    }
+
+    pub fn insert(&mut self, range: Range<T>, value: V) {
+        self.intervals.add(Interval { range, value });
+    }
+
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Iterates over all of the intervals, ordered by start and then end.
+    pub fn iter(&self) -> impl Iterator<Item = (&Range<T>, &V)> {
+        self.intervals.iter().map(|i| (&i.range, &i.value))
+    }
+
+    /// Iterates over the intervals that contain `point`, ordered by start and then end.
+    pub fn query_point<'a>(&'a self, point: &'a T) -> impl Iterator<Item = (&'a Range<T>, &'a V)> {
+        self.sublists_ending_after(point.clone())
+            .take_while(move |sublist| sublist.first().is_some_and(|i| i.range.start <= *point))
+            .flatten()
+            .filter(move |i| i.range.start <= *point && *point < i.range.end)
+            .map(|i| (&i.range, &i.value))
+    }
+
+    /// Iterates over the intervals that overlap `query`, ordered by start and then end.
+    pub fn query_overlapping(&self, query: Range<T>) -> impl Iterator<Item = (&Range<T>, &V)> {
+        let Range { start, end } = query;
+        let start_bound = end.clone();
+        self.sublists_ending_after(start.clone())
+            .take_while(move |sublist| sublist.first().is_some_and(|i| i.range.start < start_bound))
+            .flatten()
+            .filter(move |i| {
+                i.range.start < i.range.end && i.range.start < end && start < i.range.end
+            })
+            .map(|i| (&i.range, &i.value))
+    }
+
+    /// The sublists containing at least one interval that ends after `point`.
+    fn sublists_ending_after(&self, point: T) -> impl Iterator<Item = &[Interval<T, V>]> {
+        self.intervals
+            .summarized_sublists()
+            .filter(move |(_, max_end)| max_end.as_ref().is_some_and(|end| *end > point))
+            .map(|(sublist, _)| sublist)
+    }
+}
+
+impl<T: Ord + Clone, V> Default for IntervalTree<T, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(n: u32) -> (IntervalTree<u32, u32>, Vec<Range<u32>>) {
+        let mut tree = IntervalTree::new();
+        let mut ranges = Vec::new();
+        for i in 0..n {
+            let start = (i * 7919) % 10007;
+            let range = start..start + i % 50;
+            tree.insert(range.clone(), i);
+            ranges.push(range);
+        }
+        (tree, ranges)
+    }
+
+    #[test]
+    fn point_queries_match_a_scan() {
+        let (tree, ranges) = build(5000);
+        assert_eq!(5000, tree.len());
+        for p in (0..10100).step_by(37) {
+            let mut expected: Vec<&Range<u32>> = ranges.iter().filter(|r| r.contains(&p)).collect();
+            expected.sort_by_key(|r| (r.start, r.end));
+            let found: Vec<&Range<u32>> = tree.query_point(&p).map(|(r, _)| r).collect();
+            assert_eq!(expected, found);
+        }
+    }
+
+    #[test]
+    fn overlap_queries_match_a_scan() {
+        let (tree, ranges) = build(5000);
+        for start in (0..10100).step_by(101) {
+            let query = start..start + 30;
+            let mut expected: Vec<&Range<u32>> = ranges
+                .iter()
+                .filter(|r| r.start < r.end && r.start < query.end && query.start < r.end)
+                .collect();
+            expected.sort_by_key(|r| (r.start, r.end));
+            let found: Vec<&Range<u32>> = tree.query_overlapping(query).map(|(r, _)| r).collect();
+            assert_eq!(expected, found);
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let tree: IntervalTree<i32, ()> = IntervalTree::default();
+        assert!(tree.is_empty());
+        assert_eq!(0, tree.query_point(&1).count());
+        assert_eq!(0, tree.query_overlapping(-5..5).count());
+    }
+}
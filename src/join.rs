@@ -0,0 +1,240 @@
+//! Sort-merge joins between sequences that are sorted by a key.
+//!
+//! Both sides are walked forwards once, in step, so joining two sorted lists takes time
+//! proportional to their lengths plus the number of matches. Any iterators will do, as long as
+//! each yields its items in ascending order of the key being joined on, such as the iterators of
+//! two `SortedList`s. When several items on each side share a key, every combination of them is
+//! yielded.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::join::{inner_join, left_join};
+//! use sorted_collections::SortedList;
+//!
+//! let users: SortedList<(u32, &str)> =
+//!     vec![(1, "ann"), (2, "bob"), (3, "cat")].into_iter().collect();
+//! let orders: SortedList<(u32, &str)> =
+//!     vec![(1, "pen"), (1, "ink"), (3, "cup")].into_iter().collect();
+//!
+//! let joined: Vec<_> = inner_join(users.iter(), orders.iter(), |u| u.0, |o| o.0)
+//!     .map(|(u, o)| (u.1, o.1))
+//!     .collect();
+//! assert_eq!(vec![("ann", "ink"), ("ann", "pen"), ("cat", "cup")], joined);
+//!
+//! let no_orders: Vec<_> = left_join(users.iter(), orders.iter(), |u| u.0, |o| o.0)
+//!     .filter(|(_, o)| o.is_none())
+//!     .map(|(u, _)| u.1)
+//!     .collect();
+//! assert_eq!(vec!["bob"], no_orders);
+//! ```
+
+use std::cmp::Ordering;
+use std::iter::{FusedIterator, Peekable};
+
+/// An item yielded by `merge_join_by`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinItem<L, R> {
+    /// An item from the left with no match on the right.
+    Left(L),
+    /// An item from the right with no match on the left.
+    Right(R),
+    /// A matching pair.
+    Both(L, R),
+}
+
+/// Merges two sorted sequences, yielding every item from either side that has no match on the
+/// other, and every matching pair.
+///
+/// `cmp` compares an item from the left with one from the right. Items are yielded in ascending
+/// order, with unmatched items and matched pairs interleaved.
+pub fn merge_join_by<L, R, F>(left: L, right: R, cmp: F) -> MergeJoinBy<L::IntoIter, R::IntoIter, F>
+where
+    L: IntoIterator,
+    R: IntoIterator,
+    L::Item: Clone,
+    R::Item: Clone,
+    F: FnMut(&L::Item, &R::Item) -> Ordering,
+{
+    MergeJoinBy {
+        left: left.into_iter().peekable(),
+        right: right.into_iter().peekable(),
+        cmp,
+        group: Vec::new(),
+        current: None,
+        position: 0,
+    }
+}
+
+/// Yields each pair of items whose keys are equal, in ascending order of key.
+pub fn inner_join<L, R, K, FL, FR>(
+    left: L,
+    right: R,
+    mut left_key: FL,
+    mut right_key: FR,
+) -> impl Iterator<Item = (L::Item, R::Item)>
+where
+    L: IntoIterator,
+    R: IntoIterator,
+    L::Item: Clone,
+    R::Item: Clone,
+    K: Ord,
+    FL: FnMut(&L::Item) -> K,
+    FR: FnMut(&R::Item) -> K,
+{
+    merge_join_by(left, right, move |l, r| left_key(l).cmp(&right_key(r))).filter_map(|item| {
+        match item {
+            JoinItem::Both(l, r) => Some((l, r)),
+            _ => None,
+        }
+    })
+}
+
+/// Yields each item on the left paired with each item on the right with an equal key, or with
+/// `None` if there are none, in ascending order of key.
+pub fn left_join<L, R, K, FL, FR>(
+    left: L,
+    right: R,
+    mut left_key: FL,
+    mut right_key: FR,
+) -> impl Iterator<Item = (L::Item, Option<R::Item>)>
+where
+    L: IntoIterator,
+    R: IntoIterator,
+    L::Item: Clone,
+    R::Item: Clone,
+    K: Ord,
+    FL: FnMut(&L::Item) -> K,
+    FR: FnMut(&R::Item) -> K,
+{
+    merge_join_by(left, right, move |l, r| left_key(l).cmp(&right_key(r))).filter_map(|item| {
+        match item {
+            JoinItem::Left(l) => Some((l, None)),
+            JoinItem::Both(l, r) => Some((l, Some(r))),
+            JoinItem::Right(_) => None,
+        }
+    })
+}
+
+/// The iterator returned by `merge_join_by`.
+pub struct MergeJoinBy<L: Iterator, R: Iterator, F> {
+    left: Peekable<L>,
+    right: Peekable<R>,
+    cmp: F,
+    // The right items equal to the left item being matched.
+    group: Vec<R::Item>,
+    current: Option<L::Item>,
+    position: usize,
+}
+
+impl<L, R, F> Iterator for MergeJoinBy<L, R, F>
+where
+    L: Iterator,
+    R: Iterator,
+    L::Item: Clone,
+    R::Item: Clone,
+    F: FnMut(&L::Item, &R::Item) -> Ordering,
+{
+    type Item = JoinItem<L::Item, R::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let MergeJoinBy {
+            left,
+            right,
+            cmp,
+            group,
+            current,
+            position,
+        } = self;
+        loop {
+            if let Some(l) = current.as_ref() {
+                if let Some(r) = group.get(*position) {
+                    *position += 1;
+                    return Some(JoinItem::Both(l.clone(), r.clone()));
+                }
+                // The next left item may match the same group.
+                *current = left.next_if(|l| cmp(l, &group[0]) == Ordering::Equal);
+                *position = 0;
+                if current.is_some() {
+                    continue;
+                }
+                group.clear();
+            }
+
+            let order = match (left.peek(), right.peek()) {
+                (Some(l), Some(r)) => cmp(l, r),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => return None,
+            };
+            match order {
+                Ordering::Less => return left.next().map(JoinItem::Left),
+                Ordering::Greater => return right.next().map(JoinItem::Right),
+                Ordering::Equal => {
+                    let l = left.next().unwrap();
+                    while let Some(r) = right.next_if(|r| cmp(&l, r) == Ordering::Equal) {
+                        group.push(r);
+                    }
+                    *current = Some(l);
+                }
+            }
+        }
+    }
+}
+
+impl<L, R, F> FusedIterator for MergeJoinBy<L, R, F>
+where
+    L: FusedIterator,
+    R: FusedIterator,
+    L::Item: Clone,
+    R::Item: Clone,
+    F: FnMut(&L::Item, &R::Item) -> Ordering,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Joins by comparing every pair, in the order `merge_join_by` should produce.
+    fn nested_loop(left: &[(u8, u8)], right: &[(u8, u8)]) -> Vec<JoinItem<(u8, u8), (u8, u8)>> {
+        let mut keys: Vec<u8> = left.iter().chain(right).map(|x| x.0).collect();
+        keys.sort();
+        keys.dedup();
+        let mut result = Vec::new();
+        for k in keys {
+            let ls: Vec<_> = left.iter().filter(|x| x.0 == k).collect();
+            let rs: Vec<_> = right.iter().filter(|x| x.0 == k).collect();
+            match (ls.is_empty(), rs.is_empty()) {
+                (false, true) => result.extend(ls.into_iter().map(|&l| JoinItem::Left(l))),
+                (true, false) => result.extend(rs.into_iter().map(|&r| JoinItem::Right(r))),
+                _ => {
+                    for &l in &ls {
+                        result.extend(rs.iter().map(|&&r| JoinItem::Both(*l, r)));
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    quickcheck! {
+        fn prop_merge_join(left: Vec<(u8, u8)>, right: Vec<(u8, u8)>) -> bool {
+            let (mut left, mut right) = (left, right);
+            for x in left.iter_mut().chain(right.iter_mut()) {
+                x.0 %= 8;
+            }
+            left.sort();
+            right.sort();
+            let joined: Vec<_> =
+                merge_join_by(left.iter().cloned(), right.iter().cloned(), |l, r| l.0.cmp(&r.0))
+                    .collect();
+            let inner = inner_join(&left, &right, |l| l.0, |r| r.0).count();
+            let left_len = left_join(&left, &right, |l| l.0, |r| r.0).count();
+
+            let expected = nested_loop(&left, &right);
+            let both = expected.iter().filter(|x| matches!(x, JoinItem::Both(..))).count();
+            let lefts = expected.iter().filter(|x| matches!(x, JoinItem::Left(..))).count();
+            joined == expected && inner == both && left_len == both + lefts
+        }
+    }
+}
@@ -1,14 +1,64 @@
 //! Expandable, hopefully reasonably-cache friendly list types written entirely in safe Rustvisibility.
 
+#[cfg(feature = "arrow")]
+extern crate arrow_array;
+#[cfg(any(feature = "bytes", feature = "compress", feature = "mmap"))]
+extern crate bytemuck;
+#[cfg(feature = "compress")]
+extern crate lz4_flex;
+#[cfg(feature = "mmap")]
+extern crate memmap2;
 #[cfg(test)]
 #[macro_use]
 extern crate quickcheck;
+#[cfg(feature = "rand")]
+extern crate rand;
+#[cfg(feature = "rayon")]
+extern crate rayon;
 
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "bytes")]
+pub mod bytes;
+mod chunked;
+#[cfg(feature = "compress")]
+pub mod compress;
+#[cfg(feature = "delta")]
+pub mod delta;
+pub mod growth;
+mod interpolation;
+pub mod interval_tree;
+pub mod join;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+pub mod quantile;
+mod sorted_iter;
 pub mod sorted_list;
+mod sorted_read;
 mod sorted_utils;
+pub mod sorted_vec;
+mod sorted_write;
+#[cfg(feature = "stats")]
+pub mod stats;
 pub mod unsorted_list;
 
+#[cfg(feature = "compress")]
+pub use compress::ArchivedSortedList;
+pub use growth::{GrowthPolicy, LoadFactor};
+pub use interpolation::InterpolatableKey;
+pub use interval_tree::IntervalTree;
+#[cfg(feature = "mmap")]
+pub use mmap::MmapSortedSlice;
+pub use quantile::QuantileSketch;
+pub use sorted_iter::{AssertSorted, AssertSortedExt, SortedIterator};
+pub use sorted_list::counter::Counter;
+pub use sorted_list::frozen::FrozenSortedList;
 pub use sorted_list::SortedList;
+pub use sorted_read::SortedRead;
+pub use sorted_vec::SortedVec;
+pub use sorted_write::SortedWrite;
+#[cfg(feature = "stats")]
+pub use stats::OpStats;
 pub use unsorted_list::UnsortedList;
 
 use std::iter::FusedIterator;
@@ -35,6 +85,47 @@ impl<'a, T> Iterator for Iter<'a, T> {
 }
 impl<'a, T> FusedIterator for Iter<'a, T> {}
 
+pub struct IterMut<'a, T: 'a> {
+    outer: std::slice::IterMut<'a, Vec<T>>,
+    inner: std::slice::IterMut<'a, T>,
+}
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().or_else(|| {
+            self.outer.next().and_then(|x| {
+                self.inner = x.iter_mut();
+                self.next()
+            })
+        })
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.inner.len() + self.outer.len(), None)
+    }
+}
+impl<'a, T> FusedIterator for IterMut<'a, T> {}
+
+pub struct Range<'a, T: 'a> {
+    iter: Iter<'a, T>,
+    remaining: usize,
+}
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            None
+        } else {
+            self.remaining -= 1;
+            self.iter.next()
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+impl<'a, T> ExactSizeIterator for Range<'a, T> {}
+impl<'a, T> FusedIterator for Range<'a, T> {}
+
 pub struct IntoIter<T> {
     outer: std::vec::IntoIter<Vec<T>>,
     inner: std::vec::IntoIter<T>,
@@ -53,7 +144,7 @@ impl<T> Iterator for IntoIter<T> {
         (self.inner.len() + self.outer.len(), None)
     }
 }
-impl<'a, T> FusedIterator for IntoIter<T> {}
+impl<T> FusedIterator for IntoIter<T> {}
 
 #[cfg(test)]
 mod tests {
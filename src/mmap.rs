@@ -0,0 +1,157 @@
+//! A read-only sorted collection backed by a memory-mapped file.
+//!
+//! Requires the `mmap` feature. The file must contain fixed-size records that are already
+//! sorted, such as an index built offline. Queries run directly against the mapped pages, so
+//! nothing is loaded onto the heap.
+//!
+//! Mapping a file is `unsafe` (the file could be changed underneath the mapping), so this module
+//! takes an already mapped `Mmap` and leaves that decision to the caller.
+
+use super::sorted_read::SortedRead;
+use super::sorted_utils::slice_range;
+use bytemuck::{try_cast_slice, Pod, PodCastError};
+use memmap2::Mmap;
+use std::marker::PhantomData;
+use std::ops::{Index, RangeBounds};
+
+/// A sorted slice of `T` records stored in a memory-mapped file.
+///
+/// It is a logic error for the records not to be sorted according to `Ord`.
+#[derive(Debug)]
+pub struct MmapSortedSlice<T: Pod + Ord> {
+    mmap: Mmap,
+    records: PhantomData<T>,
+}
+
+impl<T: Pod + Ord> MmapSortedSlice<T> {
+    /// Wraps a mapping of sorted records.
+    ///
+    /// Fails if the mapping isn't a whole number of records, or isn't suitably aligned for `T`.
+    pub fn new(mmap: Mmap) -> Result<Self, PodCastError> {
+        try_cast_slice::<u8, T>(&mmap)?;
+        Ok(Self {
+            mmap,
+            records: PhantomData,
+        })
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        // Checked in the constructor.
+        try_cast_slice(&self.mmap).unwrap()
+    }
+
+    pub fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.as_slice().is_empty()
+    }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        self.as_slice().get(i)
+    }
+
+    pub fn contains(&self, val: &T) -> bool {
+        SortedRead::contains(self.as_slice(), val)
+    }
+
+    /// The number of records less than `val`.
+    pub fn rank(&self, val: &T) -> usize {
+        SortedRead::rank(self.as_slice(), val)
+    }
+
+    /// The records within a range of values, as a slice of the mapping.
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> &[T] {
+        let slice = self.as_slice();
+        &slice[slice_range(slice, &range)]
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.as_slice().iter()
+    }
+}
+
+impl<T: Pod + Ord> SortedRead<T> for MmapSortedSlice<T> {
+    type Iter<'a>
+        = std::slice::Iter<'a, T>
+    where
+        T: 'a;
+    type Range<'a>
+        = std::slice::Iter<'a, T>
+    where
+        T: 'a;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        self.get(i)
+    }
+
+    fn contains(&self, val: &T) -> bool {
+        self.contains(val)
+    }
+
+    fn rank(&self, val: &T) -> usize {
+        self.rank(val)
+    }
+
+    fn range<R: RangeBounds<T>>(&self, range: R) -> std::slice::Iter<'_, T> {
+        self.range(range).iter()
+    }
+
+    fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.iter()
+    }
+}
+
+impl<T: Pod + Ord> Index<usize> for MmapSortedSlice<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        &self.as_slice()[i]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::Write;
+    use std::ops::Bound;
+
+    fn mapped(name: &str, records: &[u64]) -> Mmap {
+        let path = std::env::temp_dir().join(format!("sorted_collections_{}", name));
+        File::create(&path)
+            .unwrap()
+            .write_all(bytemuck::cast_slice(records))
+            .unwrap();
+        unsafe { Mmap::map(&File::open(&path).unwrap()).unwrap() }
+    }
+
+    #[test]
+    fn queries() {
+        let records: Vec<u64> = (0..1000).map(|x| x * 2).collect();
+        let slice = MmapSortedSlice::<u64>::new(mapped("queries", &records)).unwrap();
+
+        assert_eq!(1000, slice.len());
+        assert_eq!(20, slice[10]);
+        assert_eq!(None, slice.get(1000));
+        assert!(slice.contains(&998));
+        assert!(!slice.contains(&999));
+        assert_eq!(500, slice.rank(&1000));
+        assert_eq!(&[10, 12, 14], slice.range(10..=14));
+        assert!(slice
+            .range((Bound::Included(20), Bound::Excluded(10)))
+            .is_empty());
+        assert!(slice.iter().eq(records.iter()));
+    }
+
+    #[test]
+    fn partial_record_is_an_error() {
+        let mmap = mapped("partial", &[1, 2, 3]);
+        assert!(MmapSortedSlice::<[u8; 5]>::new(mmap).is_err());
+    }
+}
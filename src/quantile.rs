@@ -0,0 +1,246 @@
+//! An approximate summary of a stream too large to keep sorted in full.
+//!
+//! A `QuantileSketch` keeps a bounded sample of the values added to it, answering the same
+//! `rank` and `quantile` questions as a `SortedList` to within a known error. Values are stored in
+//! levels: each value at level `h` stands for `2^h` of the values added. When a level fills up it
+//! is sorted and every other value is promoted to the level above, halving the space it takes.
+//!
+//! With accuracy `k`, each level holds fewer than `k` values, so a sketch of `n` values keeps
+//! about `k * log2(n / k)` of them. A rank is off by at most `n / k` for each level.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::QuantileSketch;
+//! let mut sketch = QuantileSketch::with_accuracy(100);
+//! for x in 0..100_000 {
+//!     sketch.add(x);
+//! }
+//!
+//! assert_eq!(100_000, sketch.len());
+//! let median = *sketch.quantile(0.5).unwrap();
+//! assert!((median - 50_000i32).abs() < 5_000);
+//! ```
+
+use super::SortedList;
+
+/// The accuracy used by `QuantileSketch::new`.
+const DEFAULT_ACCURACY: usize = 200;
+
+/// A bounded-memory approximation of a sorted multiset.
+#[derive(Debug, Clone)]
+pub struct QuantileSketch<T: Ord> {
+    levels: Vec<Vec<T>>,
+    // Which half of a sorted level is promoted next, per level, alternating to avoid bias.
+    odd: Vec<bool>,
+    k: usize,
+    len: usize,
+}
+
+impl<T: Ord> QuantileSketch<T> {
+    pub fn new() -> Self {
+        Self::with_accuracy(DEFAULT_ACCURACY)
+    }
+
+    /// Creates a sketch that keeps fewer than `k` values per level. Larger values of `k` use more
+    /// memory and give more accurate answers.
+    pub fn with_accuracy(k: usize) -> Self {
+        Self {
+            levels: Vec::new(),
+            odd: Vec::new(),
+            k: k.max(2),
+            len: 0,
+        }
+    }
+
+    pub fn add(&mut self, val: T) {
+        if self.levels.is_empty() {
+            self.levels.push(Vec::new());
+            self.odd.push(false);
+        }
+        self.levels[0].push(val);
+        self.len += 1;
+        self.compact_from(0);
+    }
+
+    /// Adds everything summarized by `other`, as if its values had been added to this sketch.
+    pub fn merge(&mut self, other: QuantileSketch<T>) {
+        for (h, level) in other.levels.into_iter().enumerate() {
+            if h == self.levels.len() {
+                self.levels.push(Vec::new());
+                self.odd.push(false);
+            }
+            self.levels[h].extend(level);
+        }
+        self.len += other.len;
+        self.compact_from(0);
+    }
+
+    /// The number of values that have been added.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The approximate number of values added that are less than `val`.
+    pub fn rank(&self, val: &T) -> usize {
+        self.levels
+            .iter()
+            .enumerate()
+            .map(|(h, level)| level.iter().filter(|x| *x < val).count() << h)
+            .sum()
+    }
+
+    /// A value whose rank is approximately `q * len()`, or `None` if the sketch is empty.
+    ///
+    /// Panics if `q` is not between 0 and 1.
+    pub fn quantile(&self, q: f64) -> Option<&T> {
+        assert!(
+            (0.0..=1.0).contains(&q),
+            "quantile {} is not between 0 and 1",
+            q
+        );
+        let weighted = self.weighted();
+        let target = (q * self.len as f64) as usize;
+        let mut seen = 0;
+        for &(val, weight) in &weighted {
+            seen += weight;
+            if seen > target {
+                return Some(val);
+            }
+        }
+        weighted.last().map(|&(val, _)| val)
+    }
+
+    /// Expands the sketch into a list with each kept value repeated as many times as it stands
+    /// for. The list has `len()` elements and approximately the distribution of the values
+    /// added.
+    pub fn to_sorted_list(&self) -> SortedList<T>
+    where
+        T: Clone,
+    {
+        let mut vals = Vec::with_capacity(self.len);
+        for (val, weight) in self.weighted() {
+            vals.extend(std::iter::repeat_n(val, weight).cloned());
+        }
+        let mut list = SortedList::new();
+        list.fill_sorted(vals);
+        list
+    }
+
+    /// The kept values in order, with the number of values each stands for.
+    fn weighted(&self) -> Vec<(&T, usize)> {
+        let mut weighted: Vec<(&T, usize)> = self
+            .levels
+            .iter()
+            .enumerate()
+            .flat_map(|(h, level)| level.iter().map(move |x| (x, 1 << h)))
+            .collect();
+        weighted.sort_by(|a, b| a.0.cmp(b.0));
+        weighted
+    }
+
+    /// Compacts every level from `h` upwards that has filled up.
+    fn compact_from(&mut self, mut h: usize) {
+        while h < self.levels.len() {
+            if self.levels[h].len() >= self.k {
+                self.compact(h);
+            }
+            h += 1;
+        }
+    }
+
+    /// Sorts a level and promotes every other value to the level above, keeping one back if
+    /// there is an odd number.
+    fn compact(&mut self, h: usize) {
+        if h + 1 == self.levels.len() {
+            self.levels.push(Vec::new());
+            self.odd.push(false);
+        }
+        let mut level = std::mem::take(&mut self.levels[h]);
+        level.sort();
+        if level.len() % 2 == 1 {
+            self.levels[h].push(level.pop().unwrap());
+        }
+        let offset = usize::from(self.odd[h]);
+        self.odd[h] = !self.odd[h];
+        let promoted = level.into_iter().skip(offset).step_by(2);
+        self.levels[h + 1].extend(promoted);
+    }
+}
+
+impl<T: Ord> Default for QuantileSketch<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sketches every element of an exact list.
+impl<'a, T: Ord + Clone> From<&'a SortedList<T>> for QuantileSketch<T> {
+    fn from(list: &'a SortedList<T>) -> Self {
+        let mut sketch = Self::new();
+        for x in list.iter() {
+            sketch.add(x.clone());
+        }
+        sketch
+    }
+}
+
+impl<T: Ord> Extend<T> for QuantileSketch<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.add(x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The worst rank error the sketch allows for.
+    fn bound(sketch: &QuantileSketch<u32>) -> usize {
+        sketch.len() / sketch.k * sketch.levels.len()
+    }
+
+    #[test]
+    fn ranks_are_close() {
+        let mut sketch = QuantileSketch::with_accuracy(64);
+        sketch.extend((0..50_000u32).map(|x| (x * 7919) % 50_000));
+        assert_eq!(50_000, sketch.len());
+        assert!(sketch.levels.iter().all(|level| level.len() < 64));
+        for probe in (0..50_000).step_by(1000) {
+            let error = (sketch.rank(&probe) as isize - probe as isize).unsigned_abs();
+            assert!(
+                error <= bound(&sketch),
+                "rank of {} off by {}",
+                probe,
+                error
+            );
+        }
+        assert_eq!(50_000, sketch.to_sorted_list().len());
+        assert!(*sketch.quantile(0.0).unwrap() as usize <= bound(&sketch));
+    }
+
+    #[test]
+    fn merges() {
+        let (mut a, mut b) = (
+            QuantileSketch::with_accuracy(32),
+            QuantileSketch::with_accuracy(32),
+        );
+        a.extend(0..10_000u32);
+        b.extend(10_000..20_000u32);
+        a.merge(b);
+        assert_eq!(20_000, a.len());
+        let median = *a.quantile(0.5).unwrap() as usize;
+        assert!((median as isize - 10_000).unsigned_abs() <= bound(&a));
+
+        let exact: SortedList<u32> = (0..100).collect();
+        let small = QuantileSketch::from(&exact);
+        assert!(small.to_sorted_list().iter().eq(exact.iter()));
+        assert_eq!(Some(&50), small.quantile(0.5));
+        assert_eq!(None, QuantileSketch::<u32>::new().quantile(0.5));
+    }
+}
@@ -0,0 +1,157 @@
+//! A marker for iterators that yield their items in sorted order.
+//!
+//! Collecting into a `SortedList` normally sorts the items, since an arbitrary iterator could
+//! yield them in any order. When the source is known to be sorted, as with a `BTreeSet` or a
+//! range of a `SortedList`, `collect_sorted` skips the sort and chunks the items directly into
+//! sublists.
+//!
+//! Other sorted sources can be marked with `assert_sorted`, which checks the order in debug
+//! builds.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::{AssertSortedExt, SortedIterator, SortedList};
+//! use std::collections::BTreeSet;
+//!
+//! let set: BTreeSet<u32> = vec![3, 1, 2].into_iter().collect();
+//! let list: SortedList<u32> = set.into_iter().collect_sorted();
+//! assert!(list.iter().eq([1, 2, 3].iter()));
+//!
+//! let squares: SortedList<u32> = (0..10).map(|x| x * x).assert_sorted().collect_sorted();
+//! assert_eq!(Some(&81), squares.iter().last());
+//! ```
+
+use super::sorted_list::set_ops::{Difference, Intersection, SymmetricDifference, Union};
+use super::sorted_list::Distinct;
+use super::{Range, SortedList};
+use std::collections::{btree_map, btree_set};
+use std::iter::{Cloned, Copied, Filter, Fuse, Peekable, Skip, SkipWhile, StepBy, Take, TakeWhile};
+
+/// An iterator whose items are in ascending order.
+///
+/// It is a logic error to implement this for an iterator that can yield an item less than one
+/// before it.
+pub trait SortedIterator: Iterator + Sized
+where
+    Self::Item: Ord,
+{
+    /// Collects the items into a `SortedList` without sorting them.
+    fn collect_sorted(self) -> SortedList<Self::Item> {
+        SortedList::from_sorted_iter(self)
+    }
+}
+
+/// Marks an iterator as sorted. The order is checked in debug builds, and panics if it is
+/// wrong.
+pub struct AssertSorted<I: Iterator> {
+    iter: Peekable<I>,
+}
+
+impl<I: Iterator> Iterator for AssertSorted<I>
+where
+    I::Item: Ord,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let item = self.iter.next()?;
+        if cfg!(debug_assertions) {
+            if let Some(next) = self.iter.peek() {
+                assert!(item <= *next, "assert_sorted was given unsorted items");
+            }
+        }
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Adds `assert_sorted` to every iterator.
+pub trait AssertSortedExt: Iterator + Sized {
+    /// Marks this iterator as yielding its items in ascending order, so that it can be used
+    /// where a `SortedIterator` is needed.
+    fn assert_sorted(self) -> AssertSorted<Self> {
+        AssertSorted {
+            iter: self.peekable(),
+        }
+    }
+}
+
+impl<I: Iterator> AssertSortedExt for I {}
+
+impl<I: Iterator> SortedIterator for AssertSorted<I> where I::Item: Ord {}
+impl<'a, T: Ord> SortedIterator for Range<'a, T> {}
+impl<'a, T: Ord> SortedIterator for Distinct<'a, T> {}
+impl<'a, T: Ord> SortedIterator for Union<'a, T> {}
+impl<'a, T: Ord> SortedIterator for Intersection<'a, T> {}
+impl<'a, T: Ord> SortedIterator for Difference<'a, T> {}
+impl<'a, T: Ord> SortedIterator for SymmetricDifference<'a, T> {}
+
+impl<'a, T: Ord> SortedIterator for btree_set::Iter<'a, T> {}
+impl<T: Ord> SortedIterator for btree_set::IntoIter<T> {}
+impl<'a, T: Ord> SortedIterator for btree_set::Range<'a, T> {}
+impl<'a, T: Ord> SortedIterator for btree_set::Intersection<'a, T> {}
+impl<'a, T: Ord> SortedIterator for btree_set::Union<'a, T> {}
+impl<'a, T: Ord> SortedIterator for btree_set::Difference<'a, T> {}
+impl<'a, T: Ord> SortedIterator for btree_set::SymmetricDifference<'a, T> {}
+impl<'a, K: Ord, V> SortedIterator for btree_map::Keys<'a, K, V> {}
+impl<K: Ord, V> SortedIterator for btree_map::IntoKeys<K, V> {}
+// Keys are unique, so pairs are in order whatever their values are.
+impl<'a, K: Ord, V: Ord> SortedIterator for btree_map::Iter<'a, K, V> {}
+impl<K: Ord, V: Ord> SortedIterator for btree_map::IntoIter<K, V> {}
+impl<'a, K: Ord, V: Ord> SortedIterator for btree_map::Range<'a, K, V> {}
+
+// Adaptors that keep items in the same relative order.
+impl<'a, T: 'a + Ord + Clone, I: SortedIterator<Item = &'a T>> SortedIterator for Cloned<I> {}
+impl<'a, T: 'a + Ord + Copy, I: SortedIterator<Item = &'a T>> SortedIterator for Copied<I> {}
+impl<I: SortedIterator, P: FnMut(&I::Item) -> bool> SortedIterator for Filter<I, P> where
+    I::Item: Ord
+{
+}
+impl<I: SortedIterator> SortedIterator for Fuse<I> where I::Item: Ord {}
+impl<I: SortedIterator> SortedIterator for Peekable<I> where I::Item: Ord {}
+impl<I: SortedIterator> SortedIterator for Skip<I> where I::Item: Ord {}
+impl<I: SortedIterator, P: FnMut(&I::Item) -> bool> SortedIterator for SkipWhile<I, P> where
+    I::Item: Ord
+{
+}
+impl<I: SortedIterator> SortedIterator for StepBy<I> where I::Item: Ord {}
+impl<I: SortedIterator> SortedIterator for Take<I> where I::Item: Ord {}
+impl<I: SortedIterator, P: FnMut(&I::Item) -> bool> SortedIterator for TakeWhile<I, P> where
+    I::Item: Ord
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeMap, BTreeSet};
+
+    #[test]
+    fn collects_sorted_sources() {
+        let set: BTreeSet<u32> = (0..3000).rev().collect();
+        let list = set.iter().cloned().filter(|x| x % 2 == 0).collect_sorted();
+        assert!(list.iter().cloned().eq((0..3000).step_by(2)));
+        assert!(list.as_slices().count() > 1);
+
+        let map: BTreeMap<u32, ()> = (0..10).map(|x| (x, ())).collect();
+        let keys = map.keys().copied().skip(5).collect_sorted();
+        assert!(keys.iter().cloned().eq(5..10));
+
+        let distinct = list.iter_distinct().take(3).collect_sorted();
+        assert!(distinct.iter().map(|x| **x).eq(vec![0, 2, 4]));
+        assert!(SortedList::<u32>::new()
+            .range(..)
+            .collect_sorted()
+            .is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "unsorted")]
+    #[cfg(debug_assertions)]
+    fn assert_sorted_panics() {
+        vec![1, 3, 2].into_iter().assert_sorted().collect_sorted();
+    }
+}
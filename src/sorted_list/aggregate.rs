@@ -0,0 +1,347 @@
+//! A sorted list that keeps a running aggregate of each of its sublists.
+//!
+//! This allows queries like "the sum of all values in `[a, b)`" to be answered by combining the
+//! stored aggregates of the sublists covered by the range, rather than visiting every element.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::sorted_list::aggregate::{AggregatedList, Sum};
+//! let mut list: AggregatedList<i64, Sum> = AggregatedList::new();
+//! for x in 0..100 {
+//!     list.add(x);
+//! }
+//!
+//! assert_eq!(4950, list.aggregate());
+//! assert_eq!(10 + 11 + 12, list.range_aggregate(10..13));
+//! assert!(list.contains(&50));
+//! ```
+
+use super::SortedList;
+use std::marker::PhantomData;
+use std::ops::{Add, Deref, Range, RangeBounds};
+
+/// An associative way of summarizing values, such as a sum, minimum or maximum.
+///
+/// Summaries are always combined in sorted order, so `combine` needs to be associative but not
+/// commutative.
+pub trait Aggregate<T> {
+    type Summary: Clone;
+
+    /// The summary of no values. Combining with this should have no effect.
+    fn empty() -> Self::Summary;
+
+    /// The summary of a single value.
+    fn single(val: &T) -> Self::Summary;
+
+    /// Combines the summaries of two adjacent runs of values.
+    fn combine(left: &Self::Summary, right: &Self::Summary) -> Self::Summary;
+
+    /// Summarizes a run of sorted values.
+    fn fold(vals: &[T]) -> Self::Summary {
+        vals.iter().fold(Self::empty(), |acc, x| {
+            Self::combine(&acc, &Self::single(x))
+        })
+    }
+}
+
+/// Sums values, with the default value (zero for numbers) as the empty sum.
+#[derive(Debug)]
+pub struct Sum;
+
+impl<T: Clone + Default + Add<Output = T>> Aggregate<T> for Sum {
+    type Summary = T;
+
+    fn empty() -> T {
+        T::default()
+    }
+
+    fn single(val: &T) -> T {
+        val.clone()
+    }
+
+    fn combine(left: &T, right: &T) -> T {
+        left.clone() + right.clone()
+    }
+}
+
+/// The smallest value, or `None` if there are no values.
+#[derive(Debug)]
+pub struct Min;
+
+impl<T: Clone + Ord> Aggregate<T> for Min {
+    type Summary = Option<T>;
+
+    fn empty() -> Option<T> {
+        None
+    }
+
+    fn single(val: &T) -> Option<T> {
+        Some(val.clone())
+    }
+
+    fn combine(left: &Option<T>, right: &Option<T>) -> Option<T> {
+        match (left, right) {
+            (Some(l), Some(r)) => Some(std::cmp::min(l, r).clone()),
+            (Some(x), None) | (None, Some(x)) => Some(x.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// The largest value, or `None` if there are no values.
+#[derive(Debug)]
+pub struct Max;
+
+impl<T: Clone + Ord> Aggregate<T> for Max {
+    type Summary = Option<T>;
+
+    fn empty() -> Option<T> {
+        None
+    }
+
+    fn single(val: &T) -> Option<T> {
+        Some(val.clone())
+    }
+
+    fn combine(left: &Option<T>, right: &Option<T>) -> Option<T> {
+        match (left, right) {
+            (Some(l), Some(r)) => Some(std::cmp::max(l, r).clone()),
+            (Some(x), None) | (None, Some(x)) => Some(x.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// A `SortedList` that maintains an aggregate of each sublist.
+///
+/// Mutating operations recompute the aggregates of the sublists they touched, so they cost
+/// `O(load_factor)` on top of the usual cost. All of the read-only `SortedList` methods are
+/// available through `Deref`.
+#[derive(Debug)]
+pub struct AggregatedList<T: Ord, A: Aggregate<T>> {
+    list: SortedList<T>,
+    summaries: Vec<A::Summary>, // One per sublist of `list`.
+    aggregate: PhantomData<A>,
+}
+
+impl<T: Ord, A: Aggregate<T>> AggregatedList<T, A> {
+    pub fn new() -> Self {
+        Self::from(SortedList::new())
+    }
+
+    pub fn add(&mut self, val: T) {
+        let before = self.list.chunks.lists.len();
+        let (i, _) = self.list.insert(val);
+        if self.list.chunks.lists.len() > before {
+            // The value may have landed in either half of a split, so the sublist before `i`
+            // may be new as well.
+            self.summaries.insert(i, A::empty());
+            self.refold(i.saturating_sub(1)..i + 2);
+        } else {
+            self.refold(i..i + 1);
+        }
+    }
+
+    // Popping may join the end sublist with its neighbour and split the result again, which
+    // leaves the number of sublists unchanged but rewrites both of them.
+    pub fn pop_first(&mut self) -> Option<T> {
+        let before = self.list.chunks.lists.len();
+        let rv = self.list.pop_first();
+        if self.list.chunks.lists.len() < before {
+            self.summaries.remove(1);
+        }
+        self.refold(0..2);
+        rv
+    }
+
+    pub fn pop_last(&mut self) -> Option<T> {
+        let before = self.list.chunks.lists.len();
+        let rv = self.list.pop_last();
+        let after = self.list.chunks.lists.len();
+        if after < before {
+            self.summaries.pop();
+        }
+        self.refold(after.saturating_sub(2)..after);
+        rv
+    }
+
+    /// The aggregate of every value in the list.
+    pub fn aggregate(&self) -> A::Summary {
+        self.summaries
+            .iter()
+            .fold(A::empty(), |acc, x| A::combine(&acc, x))
+    }
+
+    /// The aggregate of every value within `range`.
+    ///
+    /// Sublists that lie entirely inside the range use their stored aggregate, so only the two
+    /// boundary sublists are visited element by element.
+    pub fn range_aggregate<R: RangeBounds<T>>(&self, range: R) -> A::Summary {
+        let ((start_i, start_j), (end_i, end_j)) = self.list.range_indices(&range);
+        if (start_i, start_j) >= (end_i, end_j) {
+            return A::empty();
+        }
+
+        let lists = &self.list.chunks.lists;
+        if start_i == end_i {
+            return A::fold(&lists[start_i][start_j..end_j]);
+        }
+
+        let head = A::fold(&lists[start_i][start_j..]);
+        let middle = self.summaries[start_i + 1..end_i]
+            .iter()
+            .fold(head, |acc, x| A::combine(&acc, x));
+        A::combine(&middle, &A::fold(&lists[end_i][..end_j]))
+    }
+
+    /// Iterates over the sublists in order, along with their summaries.
+    pub(crate) fn summarized_sublists(&self) -> impl Iterator<Item = (&[T], &A::Summary)> {
+        self.list
+            .chunks
+            .lists
+            .iter()
+            .map(Vec::as_slice)
+            .zip(self.summaries.iter())
+    }
+
+    pub fn into_inner(self) -> SortedList<T> {
+        self.list
+    }
+
+    /// Recomputes the summaries of the sublists in `range` that exist.
+    fn refold(&mut self, range: Range<usize>) {
+        let lists = &self.list.chunks.lists;
+        let end = range.end.min(lists.len());
+        for (summary, list) in self.summaries[range.start..end]
+            .iter_mut()
+            .zip(&lists[range.start..end])
+        {
+            *summary = A::fold(list);
+        }
+    }
+}
+
+impl<T: Ord, A: Aggregate<T>> Deref for AggregatedList<T, A> {
+    type Target = SortedList<T>;
+
+    fn deref(&self) -> &SortedList<T> {
+        &self.list
+    }
+}
+
+impl<T: Ord, A: Aggregate<T>> From<SortedList<T>> for AggregatedList<T, A> {
+    fn from(list: SortedList<T>) -> Self {
+        let summaries = list.chunks.lists.iter().map(|l| A::fold(l)).collect();
+        Self {
+            list,
+            summaries,
+            aggregate: PhantomData,
+        }
+    }
+}
+
+impl<T: Ord, A: Aggregate<T>> Default for AggregatedList<T, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use LoadFactor;
+
+    fn naive_sum(range: std::ops::Range<i64>, vals: &[i64]) -> i64 {
+        vals.iter().filter(|x| range.contains(x)).sum()
+    }
+
+    #[test]
+    fn sum_across_sublists() {
+        let mut list: AggregatedList<i64, Sum> = AggregatedList::new();
+        let vals: Vec<i64> = (0..5000).map(|x| (x * 7919) % 5003).collect();
+        for &x in &vals {
+            list.add(x);
+        }
+        assert!(list.chunks.lists.len() > 1);
+
+        assert_eq!(vals.iter().sum::<i64>(), list.aggregate());
+        for &(a, b) in &[
+            (0, 10),
+            (5, 4000),
+            (1999, 2001),
+            (-20, 6000),
+            (30, 30),
+            (40, 20),
+        ] {
+            assert_eq!(naive_sum(a..b, &vals), list.range_aggregate(a..b));
+        }
+        assert_eq!(naive_sum(-1..3, &vals), list.range_aggregate(..=2));
+        assert_eq!(naive_sum(4990..6000, &vals), list.range_aggregate(4990..));
+    }
+
+    #[test]
+    fn min_max_after_pops() {
+        let mut min_list: AggregatedList<u32, Min> = AggregatedList::new();
+        let mut max_list: AggregatedList<u32, Max> = AggregatedList::new();
+        for x in 0..3000 {
+            min_list.add(x);
+            max_list.add(x);
+        }
+        for _ in 0..2500 {
+            min_list.pop_first();
+            max_list.pop_last();
+        }
+
+        assert_eq!(Some(2500), min_list.aggregate());
+        assert_eq!(Some(499), max_list.aggregate());
+        assert_eq!(Some(2700), min_list.range_aggregate(2700..));
+        assert_eq!(Some(99), max_list.range_aggregate(..100));
+        assert_eq!(None, max_list.range_aggregate(1000..));
+    }
+
+    #[test]
+    fn pops_after_join_and_split() {
+        let mut list: AggregatedList<i64, Sum> =
+            SortedList::with_growth_policy(LoadFactor(4)).into();
+        for x in 0..40 {
+            list.add(x);
+        }
+        for _ in 0..32 {
+            list.pop_first();
+        }
+        assert_eq!((32..40).sum::<i64>(), list.aggregate());
+
+        let mut list: AggregatedList<i64, Sum> =
+            SortedList::with_growth_policy(LoadFactor(4)).into();
+        for x in 0..40 {
+            list.add(x);
+        }
+        for _ in 0..32 {
+            list.pop_last();
+        }
+        assert_eq!((0..8).sum::<i64>(), list.aggregate());
+    }
+
+    quickcheck! {
+        fn prop_range_sum(vals: Vec<i32>, a: i32, b: i32) -> bool {
+            let vals: Vec<i64> = vals.into_iter().map(i64::from).collect();
+            let (a, b) = (i64::from(a), i64::from(b));
+            let list: AggregatedList<i64, Sum> = vals.iter().cloned().collect::<SortedList<_>>().into();
+            list.range_aggregate(a..b) == naive_sum(a..b, &vals)
+        }
+
+        fn prop_summaries_follow_splits_and_joins(ops: Vec<Result<i16, bool>>) -> bool {
+            let mut list: AggregatedList<i64, Sum> =
+                SortedList::with_growth_policy(LoadFactor(4)).into();
+            for op in ops {
+                match op {
+                    Ok(x) => list.add(i64::from(x)),
+                    Err(true) => drop(list.pop_first()),
+                    Err(false) => drop(list.pop_last()),
+                }
+            }
+            let ok = list.summarized_sublists().all(|(l, &sum)| l.iter().sum::<i64>() == sum);
+            ok && list.summaries.len() == list.chunks.lists.len()
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! A sorted list that batches insertions in an unsorted buffer.
+//!
+//! Adding to a `SortedList` costs a binary search and a shift within a sublist for every value.
+//! When values are written much more often than they are read, it's cheaper to collect them in a
+//! buffer and merge them in all at once, which is what `BufferedList` does. The buffer is merged
+//! when it fills up, when `flush` is called, or when the sorted contents are read.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::sorted_list::buffered::BufferedList;
+//! let mut list = BufferedList::with_threshold(100);
+//! for x in (0..1000).rev() {
+//!     list.add(x);
+//! }
+//!
+//! assert_eq!(1000, list.len());
+//! assert_eq!(Some(&0), list.sorted().first());
+//! assert_eq!(0, list.pending());
+//! ```
+
+use super::SortedList;
+
+/// By default, the buffer is merged into the list once it holds this many values.
+const DEFAULT_THRESHOLD: usize = 1000;
+
+/// A `SortedList` with a staging buffer for insertions.
+#[derive(Debug)]
+pub struct BufferedList<T: Ord> {
+    list: SortedList<T>,
+    buffer: Vec<T>,
+    threshold: usize,
+}
+
+impl<T: Ord> BufferedList<T> {
+    pub fn new() -> Self {
+        Self::with_threshold(DEFAULT_THRESHOLD)
+    }
+
+    /// Creates a list whose buffer is merged once it holds `threshold` values.
+    pub fn with_threshold(threshold: usize) -> Self {
+        Self {
+            list: SortedList::new(),
+            buffer: Vec::new(),
+            threshold: threshold.max(1),
+        }
+    }
+
+    /// Adds a value to the buffer, merging the buffer into the list if it is full.
+    pub fn add(&mut self, val: T) {
+        self.buffer.push(val);
+        if self.buffer.len() >= self.threshold {
+            self.flush();
+        }
+    }
+
+    /// Merges any buffered values into the list.
+    pub fn flush(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        self.buffer.sort();
+        self.list.merge_iter(self.buffer.drain(..));
+    }
+
+    /// Flushes the buffer and returns the sorted list for reading.
+    pub fn sorted(&mut self) -> &SortedList<T> {
+        self.flush();
+        &self.list
+    }
+
+    /// The number of values, including those still in the buffer.
+    pub fn len(&self) -> usize {
+        self.list.len() + self.buffer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of values waiting in the buffer.
+    pub fn pending(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Flushes the buffer and returns the sorted list.
+    pub fn into_inner(mut self) -> SortedList<T> {
+        self.flush();
+        self.list
+    }
+}
+
+impl<T: Ord> Default for BufferedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> From<SortedList<T>> for BufferedList<T> {
+    fn from(list: SortedList<T>) -> Self {
+        Self {
+            list,
+            ..Self::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flushes_at_threshold() {
+        let mut list = BufferedList::with_threshold(10);
+        for x in 0..25 {
+            list.add(x);
+        }
+        assert_eq!(25, list.len());
+        assert_eq!(5, list.pending());
+
+        list.flush();
+        assert_eq!(0, list.pending());
+        assert!(list.sorted().iter().cloned().eq(0..25));
+    }
+
+    quickcheck! {
+        fn prop_same_as_sorted_list(vals: Vec<i32>) -> bool {
+            let mut buffered = BufferedList::with_threshold(7);
+            for &x in &vals {
+                buffered.add(x);
+            }
+            let mut sorted = vals.clone();
+            sorted.sort();
+            buffered.len() == vals.len() && buffered.into_inner().iter().eq(sorted.iter())
+        }
+    }
+}
@@ -0,0 +1,237 @@
+//! A multiset that stores each distinct value once, with a count.
+//!
+//! This is a cross between Python's `collections.Counter` and a sorted list: values are kept in
+//! sorted order, so iteration and range-style queries are ordered, while repeated values only
+//! take up space once.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::Counter;
+//! let mut counter: Counter<&str> = "a b r a c a d a b r a".split(' ').collect();
+//!
+//! assert_eq!(5, counter.count(&"a"));
+//! assert_eq!(11, counter.total());
+//! assert_eq!(vec![(&"a", 5), (&"b", 2)], counter.most_common(2));
+//!
+//! counter.subtract(&"d", 1);
+//! assert_eq!(0, counter.count(&"d"));
+//! assert_eq!(4, counter.len());
+//! ```
+
+use super::SortedList;
+use std::cmp::{Ordering, Reverse};
+use std::iter::FromIterator;
+use std::ops::{AddAssign, SubAssign};
+
+/// A value and how many times it has been counted. Ordered by the value alone.
+#[derive(Debug)]
+struct Entry<T> {
+    val: T,
+    count: usize,
+}
+
+impl<T: Ord> PartialEq for Entry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.val == other.val
+    }
+}
+
+impl<T: Ord> Eq for Entry<T> {}
+
+impl<T: Ord> PartialOrd for Entry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for Entry<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.val.cmp(&other.val)
+    }
+}
+
+/// Counts occurrences of values, keeping the distinct values in sorted order.
+#[derive(Debug)]
+pub struct Counter<T: Ord> {
+    entries: SortedList<Entry<T>>, // Every entry has a count of at least one.
+    total: usize,
+}
+
+impl<T: Ord> Counter<T> {
+    pub fn new() -> Self {
+        Self {
+            entries: SortedList::new(),
+            total: 0,
+        }
+    }
+
+    /// Counts one more occurrence of `val`.
+    pub fn add(&mut self, val: T) {
+        self.add_count(val, 1);
+    }
+
+    /// Counts `n` more occurrences of `val`.
+    pub fn add_count(&mut self, val: T, n: usize) {
+        if n == 0 {
+            return;
+        }
+
+        self.total += n;
+        match self.find(&val) {
+            Some((i, j)) => self.entries.chunks.lists[i][j].count += n,
+            None => self.entries.add(Entry { val, count: n }),
+        }
+    }
+
+    /// Removes up to `n` occurrences of `val`, returning how many were removed.
+    ///
+    /// Values whose count drops to zero are removed entirely.
+    pub fn subtract(&mut self, val: &T, n: usize) -> usize {
+        let (i, j) = match self.find(val) {
+            Some(position) => position,
+            None => return 0,
+        };
+
+        let entry = &mut self.entries.chunks.lists[i][j];
+        let removed = n.min(entry.count);
+        entry.count -= removed;
+        self.total -= removed;
+        if entry.count == 0 {
+            self.entries.chunks.remove_indices((i, j));
+        }
+        removed
+    }
+
+    /// How many times `val` has been counted.
+    pub fn count(&self, val: &T) -> usize {
+        self.find(val)
+            .map_or(0, |(i, j)| self.entries.chunks.lists[i][j].count)
+    }
+
+    /// The sum of all counts.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// The number of distinct values.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns up to `k` of the most frequent values with their counts, most frequent first.
+    ///
+    /// Values that are equally frequent are ordered smallest first.
+    pub fn most_common(&self, k: usize) -> Vec<(&T, usize)> {
+        let mut counts: Vec<(&T, usize)> = self.iter().collect();
+        // Stable, so ties keep their sorted order.
+        counts.sort_by_key(|&(_, count)| Reverse(count));
+        counts.truncate(k);
+        counts
+    }
+
+    /// Iterates over the distinct values in sorted order, with their counts.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.entries.iter().map(|entry| (&entry.val, entry.count))
+    }
+
+    fn find(&self, val: &T) -> Option<(usize, usize)> {
+        let (i, j) = self.entries.bisect_indices(|entry| entry.val < *val);
+        match self.entries.chunks.get_indices((i, j)) {
+            Some(entry) if entry.val == *val => Some((i, j)),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Ord> Default for Counter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> FromIterator<T> for Counter<T> {
+    fn from_iter<F>(iter: F) -> Self
+    where
+        F: IntoIterator<Item = T>,
+    {
+        let mut counter = Self::new();
+        for x in iter {
+            counter.add(x);
+        }
+        counter
+    }
+}
+
+// `Add` and `Sub` aren't implemented, since their by-value `add` would be picked over the
+// inherent `Counter::add` in method calls.
+
+/// Sums the counts of each value.
+impl<T: Ord> AddAssign for Counter<T> {
+    fn add_assign(&mut self, other: Counter<T>) {
+        for entry in other.entries {
+            self.add_count(entry.val, entry.count);
+        }
+    }
+}
+
+/// Subtracts the counts of each value, dropping values whose count reaches zero.
+impl<T: Ord> SubAssign for Counter<T> {
+    fn sub_assign(&mut self, other: Counter<T>) {
+        for entry in other.entries {
+            self.subtract(&entry.val, entry.count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn many_values() {
+        let counter: Counter<u32> = (0..20000).map(|x| x % 3000).collect();
+        assert_eq!(3000, counter.len());
+        assert_eq!(20000, counter.total());
+        assert_eq!(7, counter.count(&1999));
+        assert_eq!(6, counter.count(&2000));
+        assert_eq!(0, counter.count(&3000));
+        assert!(counter.iter().map(|(x, _)| *x).eq(0..3000));
+        assert_eq!(vec![(&0, 7), (&1, 7)], counter.most_common(2));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let mut counter: Counter<char> = "aaabbc".chars().collect();
+
+        counter += "abbbd".chars().collect();
+        assert_eq!(
+            vec![(&'a', 4), (&'b', 5), (&'c', 1), (&'d', 1)],
+            counter.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(11, counter.total());
+
+        counter -= "abbbbbdd".chars().collect();
+        assert_eq!(
+            vec![(&'a', 3), (&'c', 1)],
+            counter.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(4, counter.total());
+    }
+
+    #[test]
+    fn subtract() {
+        let mut counter = Counter::new();
+        counter.add_count("x", 3);
+        counter.add_count("y", 0);
+        assert_eq!(1, counter.len());
+        assert_eq!(2, counter.subtract(&"x", 2));
+        assert_eq!(1, counter.subtract(&"x", 5));
+        assert_eq!(0, counter.subtract(&"x", 1));
+        assert!(counter.is_empty());
+        assert_eq!(0, counter.total());
+    }
+}
@@ -0,0 +1,438 @@
+//! Cursors that walk a sorted list one element at a time, like `LinkedList`'s.
+//!
+//! A cursor remembers the (sublist, offset) position of its current element, so stepping to a
+//! neighbour is `O(1)` rather than a fresh search. `CursorMut` can also remove the current element
+//! and insert values at the cursor, which makes passes that interleave scanning and deleting
+//! cheap.
+//!
+//! As with `LinkedList`'s cursors, there is a "ghost" position past the last element, where the
+//! cursor has no current element. Moving forwards from the ghost goes to the first element, and
+//! moving backwards from it goes to the last.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::SortedList;
+//! let mut list: SortedList<u32> = (0..10).collect();
+//!
+//! let mut cursor = list.cursor_mut_at(&3);
+//! while let Some(&x) = cursor.current() {
+//!     if x % 2 == 0 {
+//!         cursor.remove_current();
+//!     } else {
+//!         cursor.move_next();
+//!     }
+//! }
+//! assert!(list.iter().eq([0, 1, 2, 3, 5, 7, 9].iter()));
+//! ```
+
+use super::super::chunked::ChunkedStorage;
+use super::SortedList;
+
+impl<T: Ord> SortedList<T> {
+    /// A cursor on the first element, or on the ghost position if the list is empty.
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        Cursor {
+            position: Position::front(&self.chunks),
+            list: self,
+        }
+    }
+
+    /// A cursor on the last element, or on the ghost position if the list is empty.
+    pub fn cursor_back(&self) -> Cursor<'_, T> {
+        Cursor {
+            position: Position::back(&self.chunks),
+            list: self,
+        }
+    }
+
+    /// A cursor on the first element not less than `val`, found by binary search, or on the
+    /// ghost position if there is none.
+    pub fn cursor_at(&self, val: &T) -> Cursor<'_, T> {
+        Cursor {
+            position: self.position_at(val),
+            list: self,
+        }
+    }
+
+    /// A cursor that can modify the list, on the first element.
+    pub fn cursor_mut_front(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            position: Position::front(&self.chunks),
+            list: self,
+        }
+    }
+
+    /// A cursor that can modify the list, on the last element.
+    pub fn cursor_mut_back(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            position: Position::back(&self.chunks),
+            list: self,
+        }
+    }
+
+    /// A cursor that can modify the list, on the first element not less than `val`.
+    pub fn cursor_mut_at(&mut self, val: &T) -> CursorMut<'_, T> {
+        CursorMut {
+            position: self.position_at(val),
+            list: self,
+        }
+    }
+
+    fn position_at(&self, val: &T) -> Position {
+        let (i, j) = self.bisect_left_indices(val);
+        let lists = &self.chunks.lists;
+        let indices = if lists.get(i).is_some_and(|list| j < list.len()) {
+            Some((i, j))
+        } else if i + 1 < lists.len() {
+            Some((i + 1, 0))
+        } else {
+            None
+        };
+        Position {
+            indices,
+            index: self.chunks.flat_index(i, j),
+        }
+    }
+}
+
+/// Where a cursor is. `indices` is `None` at the ghost position, where `index` is the length.
+#[derive(Debug, Clone, Copy)]
+struct Position {
+    indices: Option<(usize, usize)>,
+    index: usize,
+}
+
+impl Position {
+    fn front<T>(chunks: &ChunkedStorage<T>) -> Self {
+        let mut position = Self::ghost(chunks);
+        position.move_next(chunks);
+        position
+    }
+
+    fn back<T>(chunks: &ChunkedStorage<T>) -> Self {
+        let mut position = Self::ghost(chunks);
+        position.move_prev(chunks);
+        position
+    }
+
+    fn ghost<T>(chunks: &ChunkedStorage<T>) -> Self {
+        Position {
+            indices: None,
+            index: chunks.len,
+        }
+    }
+
+    fn move_next<T>(&mut self, chunks: &ChunkedStorage<T>) {
+        self.indices = next_indices(chunks, self.indices);
+        self.index = (self.index + 1) % (chunks.len + 1);
+    }
+
+    fn move_prev<T>(&mut self, chunks: &ChunkedStorage<T>) {
+        self.indices = prev_indices(chunks, self.indices);
+        self.index = (self.index + chunks.len) % (chunks.len + 1);
+    }
+}
+
+/// The position after `indices`, where `None` is the ghost position.
+fn next_indices<T>(
+    chunks: &ChunkedStorage<T>,
+    indices: Option<(usize, usize)>,
+) -> Option<(usize, usize)> {
+    let lists = &chunks.lists;
+    match indices {
+        None if chunks.len > 0 => Some((0, 0)),
+        None => None,
+        Some((i, j)) if j + 1 < lists[i].len() => Some((i, j + 1)),
+        Some((i, _)) if i + 1 < lists.len() => Some((i + 1, 0)),
+        Some(_) => None,
+    }
+}
+
+/// The position before `indices`, where `None` is the ghost position.
+fn prev_indices<T>(
+    chunks: &ChunkedStorage<T>,
+    indices: Option<(usize, usize)>,
+) -> Option<(usize, usize)> {
+    let lists = &chunks.lists;
+    match indices {
+        None if chunks.len > 0 => Some((lists.len() - 1, lists[lists.len() - 1].len() - 1)),
+        None => None,
+        Some((i, j)) if j > 0 => Some((i, j - 1)),
+        Some((i, _)) if i > 0 => Some((i - 1, lists[i - 1].len() - 1)),
+        Some(_) => None,
+    }
+}
+
+fn get<T>(chunks: &ChunkedStorage<T>, indices: Option<(usize, usize)>) -> Option<&T> {
+    indices.map(|(i, j)| &chunks.lists[i][j])
+}
+
+/// A read-only cursor over a `SortedList`.
+///
+/// Created by `SortedList::cursor_front`, `cursor_back` or `cursor_at`.
+#[derive(Debug)]
+pub struct Cursor<'a, T: Ord + 'a> {
+    list: &'a SortedList<T>,
+    position: Position,
+}
+
+impl<'a, T: Ord> Cursor<'a, T> {
+    /// The position of the current element, or `None` at the ghost position.
+    pub fn index(&self) -> Option<usize> {
+        self.position.indices.map(|_| self.position.index)
+    }
+
+    /// The current element, or `None` at the ghost position.
+    pub fn current(&self) -> Option<&'a T> {
+        get(&self.list.chunks, self.position.indices)
+    }
+
+    /// Moves to the next element, from the last element to the ghost position, or from the
+    /// ghost position to the first element.
+    pub fn move_next(&mut self) {
+        self.position.move_next(&self.list.chunks);
+    }
+
+    /// Moves to the previous element, from the first element to the ghost position, or from the
+    /// ghost position to the last element.
+    pub fn move_prev(&mut self) {
+        self.position.move_prev(&self.list.chunks);
+    }
+
+    /// The element that `move_next` would move to.
+    pub fn peek_next(&self) -> Option<&'a T> {
+        get(
+            &self.list.chunks,
+            next_indices(&self.list.chunks, self.position.indices),
+        )
+    }
+
+    /// The element that `move_prev` would move to.
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        get(
+            &self.list.chunks,
+            prev_indices(&self.list.chunks, self.position.indices),
+        )
+    }
+}
+
+impl<'a, T: Ord> Clone for Cursor<'a, T> {
+    fn clone(&self) -> Self {
+        Cursor {
+            list: self.list,
+            position: self.position,
+        }
+    }
+}
+
+/// A cursor over a `SortedList` that can remove elements and insert values.
+///
+/// Created by `SortedList::cursor_mut_front`, `cursor_mut_back` or `cursor_mut_at`. Elements
+/// can't be modified through it, since that could put them out of order.
+#[derive(Debug)]
+pub struct CursorMut<'a, T: Ord + 'a> {
+    list: &'a mut SortedList<T>,
+    position: Position,
+}
+
+impl<'a, T: Ord> CursorMut<'a, T> {
+    /// The position of the current element, or `None` at the ghost position.
+    pub fn index(&self) -> Option<usize> {
+        self.position.indices.map(|_| self.position.index)
+    }
+
+    /// The current element, or `None` at the ghost position.
+    pub fn current(&self) -> Option<&T> {
+        get(&self.list.chunks, self.position.indices)
+    }
+
+    /// Moves to the next element, wrapping through the ghost position as `Cursor::move_next`
+    /// does.
+    pub fn move_next(&mut self) {
+        self.position.move_next(&self.list.chunks);
+    }
+
+    /// Moves to the previous element, wrapping through the ghost position as
+    /// `Cursor::move_prev` does.
+    pub fn move_prev(&mut self) {
+        self.position.move_prev(&self.list.chunks);
+    }
+
+    /// The element that `move_next` would move to.
+    pub fn peek_next(&self) -> Option<&T> {
+        get(
+            &self.list.chunks,
+            next_indices(&self.list.chunks, self.position.indices),
+        )
+    }
+
+    /// The element that `move_prev` would move to.
+    pub fn peek_prev(&self) -> Option<&T> {
+        get(
+            &self.list.chunks,
+            prev_indices(&self.list.chunks, self.position.indices),
+        )
+    }
+
+    /// A read-only cursor at the same position, borrowing this one.
+    pub fn as_cursor(&self) -> Cursor<'_, T> {
+        Cursor {
+            list: self.list,
+            position: self.position,
+        }
+    }
+
+    /// Removes and returns the current element, moving on to the element after it. Returns
+    /// `None`, and does nothing, at the ghost position.
+    ///
+    /// The cursor normally keeps its place without a search. Only when the element's sublist
+    /// becomes small enough to be joined with a neighbour is the position found again from the
+    /// index.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let (i, j) = self.position.indices?;
+        self.list.chunks.bump_version();
+        let chunks = &mut self.list.chunks;
+        let (val, joined) = chunks.remove_and_contract((i, j));
+        self.position.indices = if joined {
+            chunks.position_indices(self.position.index)
+        } else if j < chunks.lists[i].len() {
+            Some((i, j))
+        } else if i + 1 < chunks.lists.len() {
+            Some((i + 1, 0))
+        } else {
+            None
+        };
+        Some(val)
+    }
+
+    /// Adds a value just before the current element, or at the end at the ghost position,
+    /// leaving the cursor on the same element.
+    ///
+    /// The value must not be less than the previous element or greater than the current one.
+    /// This is checked in debug builds; in release builds a misplaced value leaves the list out
+    /// of order.
+    pub fn insert(&mut self, val: T) {
+        debug_assert!(
+            self.peek_prev().is_none_or(|prev| *prev <= val)
+                && self.current().is_none_or(|current| val <= *current),
+            "CursorMut::insert was given a value out of order"
+        );
+        self.list.chunks.ensure_sublist();
+        let at = self
+            .position
+            .indices
+            .unwrap_or_else(|| self.list.chunks.end_indices());
+        let inserted = self.list.insert_at(at, val);
+        self.position.index += 1;
+        if self.position.indices.is_some() {
+            self.position.indices = next_indices(&self.list.chunks, Some(inserted));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use LoadFactor;
+
+    fn list_of(vals: &[u8]) -> SortedList<u8> {
+        let mut list = SortedList::with_growth_policy(LoadFactor(4));
+        for &x in vals {
+            list.add(x);
+        }
+        list
+    }
+
+    quickcheck! {
+        fn prop_walk(vals: Vec<u8>) -> bool {
+            let list = list_of(&vals);
+            let mut sorted = vals;
+            sorted.sort();
+
+            let mut forwards = Vec::new();
+            let mut cursor = list.cursor_front();
+            while let Some(&x) = cursor.current() {
+                if cursor.index() != Some(forwards.len()) {
+                    return false;
+                }
+                forwards.push(x);
+                cursor.move_next();
+            }
+            cursor.move_prev();
+            let mut backwards = Vec::new();
+            while let Some(&x) = cursor.current() {
+                backwards.push(x);
+                cursor.move_prev();
+            }
+            backwards.reverse();
+            forwards == sorted && backwards == sorted && cursor.index().is_none()
+        }
+
+        fn prop_remove_and_insert(vals: Vec<u8>, removed: u8, inserted: Vec<u8>) -> bool {
+            let mut list = list_of(&vals);
+            let mut expected = vals;
+            expected.sort();
+            expected.retain(|x| x % 4 != removed % 4);
+
+            let mut cursor = list.cursor_mut_front();
+            while let Some(&x) = cursor.current() {
+                let index = cursor.index();
+                if x % 4 == removed % 4 {
+                    if cursor.remove_current() != Some(x) || cursor.index().is_some_and(|i| Some(i) != index) {
+                        return false;
+                    }
+                } else {
+                    cursor.move_next();
+                }
+            }
+            if !list.chunks.check() || !list.iter().eq(expected.iter()) {
+                return false;
+            }
+
+            for &x in &inserted {
+                let mut cursor = list.cursor_mut_at(&x);
+                let current = cursor.current().cloned();
+                let index = cursor.index();
+                cursor.insert(x);
+                if cursor.current().cloned() != current
+                    || cursor.index() != index.map(|i| i + 1)
+                    || cursor.peek_prev() != Some(&x)
+                {
+                    return false;
+                }
+            }
+            expected.extend(inserted);
+            expected.sort();
+            list.chunks.check() && list.iter().eq(expected.iter())
+        }
+    }
+
+    #[test]
+    fn ghost_position() {
+        let mut list = SortedList::new();
+        let mut cursor = list.cursor_mut_back();
+        assert_eq!(None, cursor.current());
+        assert_eq!(None, cursor.remove_current());
+        cursor.insert(5);
+        cursor.insert(7);
+        assert_eq!(None, cursor.index());
+        assert_eq!(Some(&7), cursor.peek_prev());
+        assert_eq!(Some(&5), cursor.peek_next());
+        cursor.move_next();
+        assert_eq!(Some(0), cursor.index());
+        assert_eq!(Some(&5), cursor.as_cursor().current());
+
+        let cursor = list.cursor_at(&6);
+        assert_eq!(Some(&7), cursor.current());
+        assert_eq!(None, list.cursor_at(&8).current());
+        assert_eq!(Some(&7), list.cursor_back().current());
+    }
+
+    #[test]
+    #[should_panic(expected = "out of order")]
+    #[cfg(debug_assertions)]
+    fn insert_checks_order() {
+        let mut list: SortedList<u32> = (0..10).collect();
+        list.cursor_mut_at(&5).insert(7);
+    }
+}
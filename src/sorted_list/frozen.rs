@@ -0,0 +1,331 @@
+//! A read-only sorted list stored in one contiguous allocation.
+//!
+//! Once a list has been built and will only be queried, freezing it removes the indirection of
+//! the sublists, and queries become plain binary searches over a slice.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::SortedList;
+//! let list: SortedList<u32> = vec![5, 1, 3].into_iter().collect();
+//! let frozen = list.freeze();
+//!
+//! assert_eq!(&[1, 3, 5], frozen.as_slice());
+//! assert!(frozen.contains(&3));
+//! assert_eq!(2, frozen.rank(&4));
+//! ```
+
+use super::super::growth::GrowthPolicy;
+use super::super::sorted_read::SortedRead;
+use super::super::sorted_utils::slice_range;
+use super::SortedList;
+use std::cmp::Ordering;
+use std::ops::{Index, Range, RangeBounds};
+use std::sync::Arc;
+
+/// An immutable sorted list backed by a boxed slice.
+///
+/// Created by `SortedList::freeze`. The list's growth policy is kept for when it is thawed.
+#[derive(Debug, Clone)]
+pub struct FrozenSortedList<T: Ord> {
+    vals: Box<[T]>,
+    policy: Option<Arc<dyn GrowthPolicy>>,
+}
+
+impl<T: Ord> FrozenSortedList<T> {
+    pub fn as_slice(&self) -> &[T] {
+        &self.vals
+    }
+
+    pub fn len(&self) -> usize {
+        self.vals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vals.is_empty()
+    }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        self.vals.get(i)
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.vals.first()
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.vals.last()
+    }
+
+    pub fn contains(&self, val: &T) -> bool {
+        self.vals.binary_search(val).is_ok()
+    }
+
+    /// The number of elements less than `val`.
+    pub fn rank(&self, val: &T) -> usize {
+        self.vals.partition_point(|x| x < val)
+    }
+
+    /// The position at which `val` would be inserted before any equal elements, which is the
+    /// number of elements less than `val`.
+    pub fn bisect_left(&self, val: &T) -> usize {
+        self.rank(val)
+    }
+
+    /// The position at which `val` would be inserted after any equal elements, which is the
+    /// number of elements less than or equal to `val`.
+    pub fn bisect_right(&self, val: &T) -> usize {
+        self.vals.partition_point(|x| x <= val)
+    }
+
+    /// The greatest element less than `val`.
+    pub fn find_lt(&self, val: &T) -> Option<&T> {
+        self.bisect_left(val).checked_sub(1).map(|i| &self.vals[i])
+    }
+
+    /// The greatest element less than or equal to `val`.
+    pub fn find_le(&self, val: &T) -> Option<&T> {
+        self.bisect_right(val).checked_sub(1).map(|i| &self.vals[i])
+    }
+
+    /// The least element greater than `val`.
+    pub fn find_gt(&self, val: &T) -> Option<&T> {
+        self.vals.get(self.bisect_right(val))
+    }
+
+    /// The least element greater than or equal to `val`.
+    pub fn find_ge(&self, val: &T) -> Option<&T> {
+        self.vals.get(self.bisect_left(val))
+    }
+
+    /// The position of an element equal to `val`, or `None` if there is none. If there are
+    /// several, this is the first of them.
+    pub fn index_of(&self, val: &T) -> Option<usize> {
+        self.first_index_of(val)
+    }
+
+    /// The position of the first element equal to `val`.
+    pub fn first_index_of(&self, val: &T) -> Option<usize> {
+        let i = self.bisect_left(val);
+        self.vals.get(i).filter(|x| *x == val).map(|_| i)
+    }
+
+    /// The position of the last element equal to `val`.
+    pub fn last_index_of(&self, val: &T) -> Option<usize> {
+        let i = self.bisect_right(val).checked_sub(1)?;
+        Some(i).filter(|&i| self.vals[i] == *val)
+    }
+
+    /// The number of elements equal to `val`.
+    pub fn count(&self, val: &T) -> usize {
+        self.equal_range(val).len()
+    }
+
+    /// The positions of the elements equal to `val`. The range is empty, starting where `val`
+    /// would be inserted, if there are none.
+    pub fn equal_range(&self, val: &T) -> Range<usize> {
+        self.bisect_left(val)..self.bisect_right(val)
+    }
+
+    /// The number of elements within a range of values.
+    pub fn count_in_range<R: RangeBounds<T>>(&self, range: R) -> usize {
+        self.range(range).len()
+    }
+
+    /// The `k`th smallest element, counting from zero. This is the same as `get(k)`.
+    pub fn kth(&self, k: usize) -> Option<&T> {
+        self.get(k)
+    }
+
+    /// The middle element, or the lower of the two middle elements if the length is even.
+    pub fn median(&self) -> Option<&T> {
+        self.get(self.len().checked_sub(1)? / 2)
+    }
+
+    /// Binary searches with a comparator, as `slice::binary_search_by` does.
+    pub fn binary_search_by<F>(&self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        self.vals.binary_search_by(f)
+    }
+
+    /// Binary searches for an element whose key, as extracted by `f`, equals `b`.
+    pub fn binary_search_by_key<B: Ord, F>(&self, b: &B, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> B,
+    {
+        self.vals.binary_search_by_key(b, f)
+    }
+
+    /// The elements within a range of values, as a slice.
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> &[T] {
+        &self.vals[slice_range(&self.vals, &range)]
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.vals.iter()
+    }
+
+    /// Converts back into a `SortedList` that can be modified, without re-sorting. The list has
+    /// the growth policy it was frozen with.
+    pub fn thaw(self) -> SortedList<T> {
+        let mut list = SortedList::new();
+        list.chunks.policy = self.policy;
+        list.fill_sorted(self.vals.into_vec());
+        list
+    }
+}
+
+impl<T: Ord> SortedList<T> {
+    /// Compacts the list into a single contiguous allocation for fast read-only queries.
+    pub fn freeze(self) -> FrozenSortedList<T> {
+        let mut vals = Vec::with_capacity(self.chunks.len);
+        for mut list in self.chunks.lists {
+            vals.append(&mut list);
+        }
+        FrozenSortedList {
+            vals: vals.into_boxed_slice(),
+            policy: self.chunks.policy,
+        }
+    }
+}
+
+impl<T: Ord> PartialEq for FrozenSortedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.vals == other.vals
+    }
+}
+
+impl<T: Ord> Eq for FrozenSortedList<T> {}
+
+impl<T: Ord> SortedRead<T> for FrozenSortedList<T> {
+    type Iter<'a>
+        = std::slice::Iter<'a, T>
+    where
+        T: 'a;
+    type Range<'a>
+        = std::slice::Iter<'a, T>
+    where
+        T: 'a;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        self.get(i)
+    }
+
+    fn contains(&self, val: &T) -> bool {
+        self.contains(val)
+    }
+
+    fn rank(&self, val: &T) -> usize {
+        self.rank(val)
+    }
+
+    fn range<R: RangeBounds<T>>(&self, range: R) -> std::slice::Iter<'_, T> {
+        self.range(range).iter()
+    }
+
+    fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.iter()
+    }
+}
+
+impl<T: Ord> Index<usize> for FrozenSortedList<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        &self.vals[i]
+    }
+}
+
+impl<T: Ord> IntoIterator for FrozenSortedList<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> std::vec::IntoIter<T> {
+        self.vals.into_vec().into_iter()
+    }
+}
+
+impl<'a, T: Ord> IntoIterator for &'a FrozenSortedList<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> std::slice::Iter<'a, T> {
+        self.vals.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn freeze_and_thaw() {
+        let list: SortedList<u32> = (0..5000).rev().collect();
+        let frozen = list.freeze();
+
+        assert_eq!(5000, frozen.len());
+        assert_eq!(Some(&0), frozen.first());
+        assert_eq!(Some(&4999), frozen.last());
+        assert_eq!(1234, frozen[1234]);
+        assert_eq!(&[10, 11, 12], frozen.range(10..13));
+        assert_eq!(100, frozen.rank(&100));
+
+        let mut thawed = frozen.thaw();
+        thawed.add(5000);
+        assert!(thawed.iter().cloned().eq(0..5001));
+    }
+
+    #[test]
+    fn searches() {
+        let frozen = vec![1, 3, 3, 3, 7]
+            .into_iter()
+            .collect::<SortedList<u32>>()
+            .freeze();
+        assert_eq!((1, 4), (frozen.bisect_left(&3), frozen.bisect_right(&3)));
+        assert_eq!(1..4, frozen.equal_range(&3));
+        assert_eq!(4..4, frozen.equal_range(&5));
+        assert_eq!(3, frozen.count(&3));
+        assert_eq!(4, frozen.count_in_range(2..=7));
+        assert_eq!(
+            (Some(1), Some(3)),
+            (frozen.index_of(&3), frozen.last_index_of(&3))
+        );
+        assert_eq!(None, frozen.index_of(&4));
+        assert_eq!(
+            (Some(&1), Some(&3)),
+            (frozen.find_lt(&3), frozen.find_le(&3))
+        );
+        assert_eq!(
+            (Some(&7), Some(&3)),
+            (frozen.find_gt(&3), frozen.find_ge(&3))
+        );
+        assert_eq!((None, None), (frozen.find_lt(&1), frozen.find_gt(&7)));
+        assert_eq!((Some(&3), Some(&3)), (frozen.kth(2), frozen.median()));
+        assert_eq!(Err(4), frozen.binary_search_by(|x| x.cmp(&5)));
+        assert_eq!(Ok(4), frozen.binary_search_by_key(&14, |x| x * 2));
+    }
+
+    #[test]
+    fn thaw_keeps_policy() {
+        let mut list = SortedList::with_load_factor(4);
+        list.extend(0..100);
+        let thawed = list.freeze().thaw();
+        assert!(thawed.chunks.check());
+        assert_eq!(4, thawed.chunks.chunk_size());
+        assert!(thawed.chunks.lists.len() > 1);
+    }
+
+    #[test]
+    fn empty() {
+        let frozen = SortedList::<i8>::new().freeze();
+        assert!(frozen.is_empty());
+        assert_eq!(None, frozen.first());
+        assert!(frozen.range(..).is_empty());
+        assert!(frozen.thaw().is_empty());
+    }
+}
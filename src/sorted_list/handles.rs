@@ -0,0 +1,169 @@
+//! A sorted list whose elements can be looked up by handles that outlive any rebalancing.
+//!
+//! Positions in a `SortedList` move whenever anything is inserted or removed before them, and
+//! sublists are split and joined as the list grows and shrinks. `HandleList` hands out a
+//! `Handle` for each value it stores; the handle stays valid until that value is removed.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::sorted_list::handles::HandleList;
+//! let mut list = HandleList::new();
+//! let five = list.add_with_handle(5);
+//! for x in 0..1000 {
+//!     list.add_with_handle(x);
+//! }
+//!
+//! assert_eq!(Some(&5), list.get_by_handle(five));
+//! assert_eq!(Some(5), list.remove_by_handle(five));
+//! assert_eq!(None, list.get_by_handle(five));
+//! assert_eq!(1000, list.len());
+//! ```
+
+use super::SortedList;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Identifies a value stored in a `HandleList`.
+///
+/// Handles are never reused, so a handle to a removed value won't find a different value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Handle(u64);
+
+/// A `SortedList` that can find and remove values by `Handle`.
+///
+/// Each value is stored once, behind an `Arc` shared by the list, where it sits alongside its
+/// handle, and a table from handles to values. Looking up a handle is `O(1)`, and removing by
+/// handle is a binary search for the stored pair.
+#[derive(Debug)]
+pub struct HandleList<T: Ord> {
+    list: SortedList<(Arc<T>, Handle)>,
+    slots: HashMap<Handle, Arc<T>>,
+    next: u64,
+}
+
+impl<T: Ord> HandleList<T> {
+    pub fn new() -> Self {
+        Self {
+            list: SortedList::new(),
+            slots: HashMap::new(),
+            next: 0,
+        }
+    }
+
+    /// Adds a value, returning a handle to it.
+    pub fn add_with_handle(&mut self, val: T) -> Handle {
+        let handle = Handle(self.next);
+        self.next += 1;
+        let val = Arc::new(val);
+        self.slots.insert(handle, Arc::clone(&val));
+        self.list.add((val, handle));
+        handle
+    }
+
+    /// The value with the given handle, or `None` if it has been removed.
+    pub fn get_by_handle(&self, handle: Handle) -> Option<&T> {
+        self.slots.get(&handle).map(|val| &**val)
+    }
+
+    /// The current position of the value with the given handle.
+    pub fn index_of_handle(&self, handle: Handle) -> Option<usize> {
+        let val = self.slots.get(&handle)?;
+        self.list
+            .binary_search_by(|(x, h)| (x, h).cmp(&(val, &handle)))
+            .ok()
+    }
+
+    /// Removes the value with the given handle, returning it.
+    pub fn remove_by_handle(&mut self, handle: Handle) -> Option<T> {
+        let val = self.slots.remove(&handle)?;
+        let pair = (val, handle);
+        self.list.take(&pair);
+        // The table and the list held the only references, and both are gone now.
+        Arc::try_unwrap(pair.0).ok()
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Iterates over the values in order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.list.iter().map(|(val, _)| &**val)
+    }
+
+    /// Iterates over the values in order, along with their handles.
+    pub fn iter_with_handles(&self) -> impl Iterator<Item = (&T, Handle)> {
+        self.list.iter().map(|(val, handle)| (&**val, *handle))
+    }
+}
+
+impl<T: Ord> Default for HandleList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handles_survive_rebalancing() {
+        let mut list = HandleList::new();
+        let handles: Vec<Handle> = (0..5000)
+            .map(|x| list.add_with_handle((x * 7919) % 5003))
+            .collect();
+        assert!(list.list.chunks.lists.len() > 1);
+
+        for (x, &handle) in handles.iter().enumerate().step_by(2) {
+            assert_eq!(Some((x * 7919) % 5003), list.remove_by_handle(handle));
+        }
+        assert_eq!(2500, list.len());
+        assert_eq!(None, list.remove_by_handle(handles[0]));
+
+        for (x, &handle) in handles.iter().enumerate().skip(1).step_by(2) {
+            let val = (x * 7919) % 5003;
+            assert_eq!(Some(&val), list.get_by_handle(handle));
+            let index = list.index_of_handle(handle).unwrap();
+            assert_eq!(val, *list.list[index].0);
+        }
+        assert!(list.iter().is_sorted());
+    }
+
+    #[test]
+    fn equal_values_have_distinct_handles() {
+        let mut list = HandleList::new();
+        let a = list.add_with_handle("x");
+        let b = list.add_with_handle("x");
+        assert_ne!(a, b);
+        assert_eq!(Some(0), list.index_of_handle(a));
+        assert_eq!(Some(1), list.index_of_handle(b));
+
+        list.remove_by_handle(a);
+        assert_eq!(Some(0), list.index_of_handle(b));
+        assert_eq!(
+            vec![(&"x", b)],
+            list.iter_with_handles().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn values_are_stored_once() {
+        // Deliberately not `Clone`.
+        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+        struct Value(u32);
+
+        let mut list = HandleList::new();
+        let handles: Vec<Handle> = (0..2000).map(|x| list.add_with_handle(Value(x))).collect();
+        let (val, handle) = &list.list[700];
+        assert!(Arc::ptr_eq(val, &list.slots[handle]));
+        assert_eq!(2, Arc::strong_count(val));
+
+        assert_eq!(Some(Value(700)), list.remove_by_handle(handles[700]));
+        assert_eq!(Some(&Value(701)), list.get_by_handle(handles[701]));
+    }
+}
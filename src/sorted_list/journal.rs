@@ -0,0 +1,285 @@
+//! A sorted list that remembers its recent changes so they can be undone and redone.
+//!
+//! Every `add` and `remove` on a `JournaledList` is recorded as an edit holding the value that
+//! went in or came out, and its position. Undoing an edit applies its inverse, which is as cheap
+//! as the edit itself, so no snapshots of the list are taken. Only the most recent edits, up to
+//! the journal's depth, are kept.
+//!
+//! Edits are undone and redone in order, so the list is always back in the state the edit left
+//! it in, and the position picks out the exact element even among ones that `Ord` considers
+//! equal.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::sorted_list::journal::JournaledList;
+//! let mut list = JournaledList::with_depth(10);
+//! list.add(3);
+//! list.add(1);
+//! list.remove(&3);
+//!
+//! assert_eq!(2, list.undo(2));
+//! assert_eq!(vec![3], list.iter().cloned().collect::<Vec<_>>());
+//! assert_eq!(1, list.redo(1));
+//! assert_eq!(vec![1, 3], list.iter().cloned().collect::<Vec<_>>());
+//! ```
+
+use super::{Iter, SortedList};
+use std::collections::VecDeque;
+
+/// By default, this many edits are kept for undoing.
+const DEFAULT_DEPTH: usize = 100;
+
+/// A change made to the list, holding the value that was added or removed and its index.
+#[derive(Debug)]
+enum Edit<T> {
+    Added(T, usize),
+    Removed(T, usize),
+}
+
+/// A `SortedList` with bounded undo and redo history.
+#[derive(Debug)]
+pub struct JournaledList<T: Ord> {
+    list: SortedList<T>,
+    undo: VecDeque<Edit<T>>,
+    redo: Vec<Edit<T>>,
+    depth: usize,
+}
+
+impl<T: Ord> JournaledList<T> {
+    pub fn new() -> Self {
+        Self::with_depth(DEFAULT_DEPTH)
+    }
+
+    /// Creates a list that keeps its `depth` most recent edits for undoing.
+    pub fn with_depth(depth: usize) -> Self {
+        Self {
+            list: SortedList::new(),
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            depth,
+        }
+    }
+
+    /// Adds a value, recording the edit. Any undone edits can no longer be redone.
+    pub fn add(&mut self, val: T)
+    where
+        T: Clone,
+    {
+        let index = self.list.add_with_index(val.clone());
+        self.record(Edit::Added(val, index));
+    }
+
+    /// Removes one element equal to `val`, recording the edit if there was one. Any undone edits
+    /// can no longer be redone.
+    pub fn remove(&mut self, val: &T) -> bool {
+        let index = self.list.bisect_left(val);
+        if self.list.get(index) != Some(val) {
+            return false;
+        }
+        let removed = self.list.remove_index(index);
+        self.record(Edit::Removed(removed, index));
+        true
+    }
+
+    /// Undoes up to `n` of the most recent edits, returning how many were undone.
+    pub fn undo(&mut self, n: usize) -> usize
+    where
+        T: Clone,
+    {
+        let mut done = 0;
+        while done < n {
+            let edit = match self.undo.pop_back() {
+                Some(edit) => edit,
+                None => break,
+            };
+            let inverse = self.revert(edit);
+            self.redo.push(inverse);
+            done += 1;
+        }
+        done
+    }
+
+    /// Redoes up to `n` of the most recently undone edits, returning how many were redone.
+    pub fn redo(&mut self, n: usize) -> usize
+    where
+        T: Clone,
+    {
+        let mut done = 0;
+        while done < n {
+            let edit = match self.redo.pop() {
+                Some(edit) => edit,
+                None => break,
+            };
+            let inverse = self.revert(edit);
+            self.undo.push_back(inverse);
+            done += 1;
+        }
+        done
+    }
+
+    /// The number of edits that can be undone.
+    pub fn undo_len(&self) -> usize {
+        self.undo.len()
+    }
+
+    /// The number of undone edits that can be redone.
+    pub fn redo_len(&self) -> usize {
+        self.redo.len()
+    }
+
+    /// Forgets all of the history, keeping the contents.
+    pub fn clear_history(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// The underlying list.
+    pub fn sorted(&self) -> &SortedList<T> {
+        &self.list
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.list.iter()
+    }
+
+    /// Returns the list, discarding the history.
+    pub fn into_inner(self) -> SortedList<T> {
+        self.list
+    }
+
+    fn record(&mut self, edit: Edit<T>) {
+        self.redo.clear();
+        if self.depth == 0 {
+            return;
+        }
+        if self.undo.len() == self.depth {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(edit);
+    }
+
+    /// Applies the inverse of an edit, returning the edit that would put it back.
+    fn revert(&mut self, edit: Edit<T>) -> Edit<T>
+    where
+        T: Clone,
+    {
+        match edit {
+            Edit::Added(val, index) => {
+                let removed = self.list.remove_index(index);
+                debug_assert!(removed == val, "a journaled value went missing");
+                Edit::Removed(removed, index)
+            }
+            Edit::Removed(val, index) => {
+                let chunks = &mut self.list.chunks;
+                chunks.ensure_sublist();
+                let at = chunks
+                    .position_indices(index)
+                    .unwrap_or_else(|| chunks.end_indices());
+                self.list.insert_at(at, val.clone());
+                Edit::Added(val, index)
+            }
+        }
+    }
+}
+
+impl<T: Ord> Default for JournaledList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> From<SortedList<T>> for JournaledList<T> {
+    fn from(list: SortedList<T>) -> Self {
+        Self {
+            list,
+            ..Self::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::Keyed;
+    use super::*;
+
+    #[test]
+    fn depth_is_bounded() {
+        let mut list = JournaledList::with_depth(3);
+        for x in 0..10 {
+            list.add(x);
+        }
+        assert_eq!(3, list.undo_len());
+        assert_eq!(3, list.undo(5));
+        assert!(list.iter().cloned().eq(0..7));
+        assert_eq!(0, list.undo(1));
+
+        assert_eq!(3, list.redo_len());
+        list.add(20);
+        assert_eq!(0, list.redo_len());
+        assert_eq!(0, list.redo(1));
+
+        let mut list = JournaledList::with_depth(0);
+        list.add(1);
+        assert_eq!(0, list.undo(1));
+        assert_eq!(1, list.len());
+    }
+
+    #[test]
+    fn undo_restores_exact_elements() {
+        let payloads = |list: &JournaledList<Keyed>| -> Vec<&'static str> {
+            list.iter().map(|x| x.payload).collect()
+        };
+        let mut list = JournaledList::new();
+        let mut states = vec![payloads(&list)];
+        for &payload in &["a", "b", "c", "d"] {
+            list.add(Keyed { key: 1, payload });
+            states.push(payloads(&list));
+        }
+        for _ in 0..2 {
+            assert!(list.remove(&Keyed {
+                key: 1,
+                payload: "?"
+            }));
+            states.push(payloads(&list));
+        }
+
+        for state in states.iter().rev().skip(1) {
+            assert_eq!(1, list.undo(1));
+            assert_eq!(*state, payloads(&list));
+        }
+        for state in states.iter().skip(1) {
+            assert_eq!(1, list.redo(1));
+            assert_eq!(*state, payloads(&list));
+        }
+    }
+
+    quickcheck! {
+        fn prop_undo_redo(vals: Vec<(bool, u8)>) -> bool {
+            let mut list = JournaledList::with_depth(vals.len());
+            let mut states = vec![Vec::new()];
+            for &(add, x) in &vals {
+                if add {
+                    list.add(x);
+                } else if !list.remove(&x) {
+                    continue;
+                }
+                states.push(list.iter().cloned().collect());
+            }
+
+            let n = states.len() - 1;
+            let mut ok = list.undo(n) == n && list.iter().eq(states[0].iter());
+            for state in states.iter().skip(1) {
+                ok &= list.redo(1) == 1 && list.iter().eq(state.iter());
+            }
+            ok && list.redo(1) == 0 && list.sorted().chunks.check()
+        }
+    }
+}
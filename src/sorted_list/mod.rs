@@ -23,14 +23,40 @@
 //! assert!(!list.contains(&1));
 //! ```
 
+pub mod aggregate;
+pub mod buffered;
+pub mod counter;
+pub mod cursor;
+pub mod frozen;
+pub mod handles;
+pub mod journal;
+pub mod set_ops;
 #[cfg(test)]
 mod tests;
+pub mod transaction;
 
-use super::sorted_utils::{insert_list_of_lists, DEFAULT_LOAD_FACTOR};
-use super::{IntoIter, Iter};
+use super::chunked::ChunkedStorage;
+use super::growth::{GrowthPolicy, LoadFactor};
+use super::interpolation::{interpolation_partition_point, InterpolatableKey};
+use super::sorted_read::SortedRead;
+use super::sorted_utils::{find_list_of_lists, merge_sorted, sorted_distinct};
+use super::sorted_write::SortedWrite;
+#[cfg(feature = "stats")]
+use super::stats::OpStats;
+use super::{IntoIter, Iter, IterMut, Range};
+#[cfg(feature = "rand")]
+use rand::Rng;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::cell::Cell;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
 use std::default::Default;
-use std::iter::FromIterator;
-use std::ops::{Index, IndexMut};
+use std::error::Error;
+use std::fmt;
+use std::iter::{Extend, FromIterator, FusedIterator, Peekable};
+use std::ops::Bound::{self, Excluded, Included, Unbounded};
+use std::ops::{Index, IndexMut, RangeBounds, Sub};
 
 /// A sorted list with no `unsafe` code.
 ///
@@ -38,133 +64,1957 @@ use std::ops::{Index, IndexMut};
 /// to any other item, as determined by the `Ord` trait, changes while it is in the heap (similar
 /// to the standard library collections). This is normally only possible through `Cell`, `RefCell`,
 /// global state, I/O, or unsafe code.
+///
+/// With the `check-ord` feature, each new value's comparisons with its neighbours are checked
+/// against the `Ord` contract as it is inserted, and a violation panics with a description of
+/// what went wrong. This is a debugging aid for comparators that aren't a total order, such as
+/// ones built on floats.
 #[derive(Debug)]
 pub struct SortedList<T: Ord> {
-    lists: Vec<Vec<T>>, // There is always at least one element in the outer list.
-    load_factor: usize,
-    len: usize,
+    chunks: ChunkedStorage<T>,
 }
 
-impl<T: Ord> SortedList<T> {
-    pub fn new() -> Self {
-        Self {
-            lists: vec![Vec::new()],
-            load_factor: DEFAULT_LOAD_FACTOR,
-            len: 0,
+impl<T: Ord> SortedList<T> {
+    /// Creates an empty list. No memory is allocated until the first value is added.
+    pub const fn new() -> Self {
+        Self {
+            chunks: ChunkedStorage::new(),
+        }
+    }
+
+    /// Creates a list whose sublists are split and joined according to `policy`.
+    pub fn with_growth_policy<P: GrowthPolicy + 'static>(policy: P) -> Self {
+        Self {
+            chunks: ChunkedStorage::with_policy(Box::new(policy)),
+        }
+    }
+
+    /// Creates a list whose sublists are kept between half and double `load_factor` elements,
+    /// in place of the default of 1000. Smaller sublists make insertion cheaper for large
+    /// elements; larger ones make scans faster for small ones.
+    ///
+    /// This is shorthand for `with_growth_policy(LoadFactor(load_factor))`. Panics if
+    /// `load_factor` is zero.
+    pub fn with_load_factor(load_factor: usize) -> Self {
+        assert!(load_factor > 0, "load factor must be at least 1");
+        Self::with_growth_policy(LoadFactor(load_factor))
+    }
+
+    /// Creates an empty list with sublists already allocated for `capacity` elements, so that
+    /// filling it doesn't reallocate as sublists grow and split.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut list = Self::new();
+        list.reserve(capacity);
+        list
+    }
+
+    /// Allocates sublists for at least `additional` more elements, sized by the growth policy.
+    ///
+    /// Each new sublist has room to grow to twice the chunk size before it is split, and is held
+    /// in reserve until a split needs it, along with space for it in the outer vector. Sublists
+    /// the list already has are not grown. Panics if the new capacity overflows `usize`.
+    pub fn reserve(&mut self, additional: usize) {
+        self.chunks.reserve(additional);
+    }
+
+    /// The work done by the list since it was created or its counts were reset.
+    #[cfg(feature = "stats")]
+    pub fn op_stats(&self) -> OpStats {
+        self.chunks.counters.snapshot()
+    }
+
+    /// Sets all of the operation counts back to zero.
+    #[cfg(feature = "stats")]
+    pub fn reset_op_stats(&mut self) {
+        self.chunks.counters.reset();
+    }
+
+    pub fn contains(&self, val: &T) -> bool {
+        self.chunks.lists.iter().any(|list| list.contains(val))
+    }
+
+    pub fn add(&mut self, new_val: T) {
+        self.insert(new_val);
+    }
+
+    /// Adds a value and returns the index it was inserted at.
+    ///
+    /// This is useful for keeping other positional data in step with the list. Computing the
+    /// index takes time proportional to the number of sublists, so prefer `add` when the index
+    /// isn't needed.
+    pub fn add_with_index(&mut self, new_val: T) -> usize {
+        let (i, j) = self.insert(new_val);
+        self.chunks.flat_index(i, j)
+    }
+
+    /// Removes one element equal to `val`, returning whether there was one.
+    pub fn remove(&mut self, val: &T) -> bool {
+        self.take(val).is_some()
+    }
+
+    /// Removes and returns one element equal to `val`, which may differ from `val` in ways that
+    /// `Ord` ignores, or returns `None` if there is no such element.
+    ///
+    /// The element is found by binary search, and its sublist is joined to a neighbour if it
+    /// becomes too small, as when popping.
+    pub fn take(&mut self, val: &T) -> Option<T> {
+        let (i, j) = self.bisect_left_indices(val);
+        if self.chunks.get_indices((i, j)) != Some(val) {
+            return None;
+        }
+        self.chunks.bump_version();
+        Some(self.chunks.remove_indices((i, j)))
+    }
+
+    /// The position at which `val` would be inserted before any equal elements, which is the
+    /// number of elements less than `val`.
+    pub fn bisect_left(&self, val: &T) -> usize {
+        let (i, j) = self.bisect_left_indices(val);
+        self.chunks.flat_index(i, j)
+    }
+
+    /// The position at which `val` would be inserted after any equal elements, which is the
+    /// number of elements less than or equal to `val`.
+    pub fn bisect_right(&self, val: &T) -> usize {
+        let (i, j) = self.bisect_right_indices(val);
+        self.chunks.flat_index(i, j)
+    }
+
+    /// The greatest element less than `val`.
+    pub fn find_lt(&self, val: &T) -> Option<&T> {
+        self.before_indices(self.bisect_left_indices(val))
+    }
+
+    /// The greatest element less than or equal to `val`.
+    pub fn find_le(&self, val: &T) -> Option<&T> {
+        self.before_indices(self.bisect_right_indices(val))
+    }
+
+    /// The least element greater than `val`.
+    pub fn find_gt(&self, val: &T) -> Option<&T> {
+        self.chunks.get_indices(self.bisect_right_indices(val))
+    }
+
+    /// The least element greater than or equal to `val`.
+    pub fn find_ge(&self, val: &T) -> Option<&T> {
+        self.chunks.get_indices(self.bisect_left_indices(val))
+    }
+
+    /// The position of an element equal to `val`, or `None` if there is none. If there are
+    /// several, this is the first of them.
+    pub fn index_of(&self, val: &T) -> Option<usize> {
+        self.first_index_of(val)
+    }
+
+    /// The position of the first element equal to `val`, found by binary search.
+    pub fn first_index_of(&self, val: &T) -> Option<usize> {
+        let (i, j) = self.bisect_left_indices(val);
+        if self.chunks.get_indices((i, j)) == Some(val) {
+            Some(self.chunks.flat_index(i, j))
+        } else {
+            None
+        }
+    }
+
+    /// The position of the last element equal to `val`, found by binary search.
+    pub fn last_index_of(&self, val: &T) -> Option<usize> {
+        match self.bisect_right(val).checked_sub(1) {
+            Some(last) if self.get(last) == Some(val) => Some(last),
+            _ => None,
+        }
+    }
+
+    /// The number of elements equal to `val`.
+    pub fn count(&self, val: &T) -> usize {
+        self.equal_range(val).len()
+    }
+
+    /// The positions of the elements equal to `val`, from the first element not less than it to
+    /// the first element greater than it. The range is empty, starting where `val` would be
+    /// inserted, if there are none.
+    ///
+    /// ```
+    /// use sorted_collections::SortedList;
+    /// let list: SortedList<u32> = vec![1, 3, 3, 3, 7].into_iter().collect();
+    /// assert_eq!(1..4, list.equal_range(&3));
+    /// assert_eq!(4..4, list.equal_range(&5));
+    /// ```
+    pub fn equal_range(&self, val: &T) -> std::ops::Range<usize> {
+        self.bisect_left(val)..self.bisect_right(val)
+    }
+
+    /// The number of elements within a range of values, found from the positions of its ends
+    /// without visiting the elements between them. An inverted range contains nothing.
+    ///
+    /// ```
+    /// use sorted_collections::SortedList;
+    /// let list: SortedList<u32> = (0..1000).map(|x| x / 2).collect();
+    /// assert_eq!(20, list.count_in_range(100..110));
+    /// assert_eq!(0, list.count_in_range(600..));
+    /// ```
+    pub fn count_in_range<R: RangeBounds<T>>(&self, range: R) -> usize {
+        let (start, end) = self.range_indices(&range);
+        if start < end {
+            self.chunks.flat_index(end.0, end.1) - self.chunks.flat_index(start.0, start.1)
+        } else {
+            0
+        }
+    }
+
+    /// Removes and returns the element at position `i`. Panics if `i` is out of bounds.
+    pub fn remove_index(&mut self, i: usize) -> T {
+        let len = self.len();
+        match self.try_remove_index(i) {
+            Some(val) => val,
+            None => panic!("index {} out of range for list of length {}", i, len),
+        }
+    }
+
+    /// Removes and returns the element at position `i`, or returns `None` if it is out of
+    /// bounds.
+    pub fn try_remove_index(&mut self, i: usize) -> Option<T> {
+        let indices = self.chunks.position_indices(i)?;
+        self.chunks.bump_version();
+        Some(self.chunks.remove_indices(indices))
+    }
+
+    /// Removes every element equal to `val`, returning how many there were.
+    ///
+    /// The run of equal elements is removed with one range removal from each sublist it spans.
+    pub fn remove_all(&mut self, val: &T) -> usize {
+        let (start, end) = self.range_indices(&(val..=val));
+        self.remove_between(start, end)
+    }
+
+    /// Removes the elements within a range of values, returning an iterator over them in order.
+    ///
+    /// The elements are removed straight away, whether or not the iterator is used. Sublists
+    /// that fall entirely inside the range are moved out whole rather than copied.
+    ///
+    /// ```
+    /// use sorted_collections::SortedList;
+    /// let mut timestamps: SortedList<u64> = (100..200).collect();
+    /// let expired = timestamps.drain_range(..150);
+    /// assert_eq!(50, expired.count());
+    /// assert_eq!(Some(&150), timestamps.first());
+    /// ```
+    pub fn drain_range<R: RangeBounds<T>>(&mut self, range: R) -> impl Iterator<Item = T> {
+        let (start, end) = self.range_indices(&range);
+        self.drain_between(start, end).into_iter().flatten()
+    }
+
+    /// Splits the list in two at a value, keeping the elements less than `at` and returning a
+    /// list of the rest with the same growth policy.
+    ///
+    /// Only the sublist that `at` falls in is divided; the sublists after it are moved whole.
+    ///
+    /// ```
+    /// use sorted_collections::SortedList;
+    /// let mut scores: SortedList<u32> = vec![40, 95, 62, 71, 88, 55].into_iter().collect();
+    /// let passed = scores.split_off(&60);
+    /// assert!(scores.iter().eq(&[40, 55]));
+    /// assert!(passed.iter().eq(&[62, 71, 88, 95]));
+    /// ```
+    pub fn split_off(&mut self, at: &T) -> SortedList<T> {
+        self.chunks.bump_version();
+        let indices = self.bisect_left_indices(at);
+        SortedList {
+            chunks: self.chunks.split_off_indices(indices),
+        }
+    }
+
+    /// Splits the list in two at a position, keeping the first `i` elements and returning a list
+    /// of the rest with the same growth policy. Panics if `i` is greater than the length.
+    ///
+    /// As with `split_off`, only one sublist is divided and the rest are moved whole.
+    pub fn split_at_index(&mut self, i: usize) -> SortedList<T> {
+        let len = self.len();
+        assert!(
+            i <= len,
+            "index {} out of range for list of length {}",
+            i,
+            len
+        );
+        self.chunks.bump_version();
+        let indices = self
+            .chunks
+            .position_indices(i)
+            .unwrap_or_else(|| self.chunks.end_indices());
+        SortedList {
+            chunks: self.chunks.split_off_indices(indices),
+        }
+    }
+
+    /// Removes one element equal to `val`, returning the index it was removed from, or `None`
+    /// if there was no such element.
+    pub fn remove_with_index(&mut self, val: &T) -> Option<usize> {
+        let (i, j) = self.bisect_left_indices(val);
+        if self.chunks.get_indices((i, j)) != Some(val) {
+            return None;
+        }
+        self.chunks.bump_version();
+
+        let index = self.chunks.flat_index(i, j);
+        self.chunks.remove_indices((i, j));
+        Some(index)
+    }
+
+    /// Adds a value, returning the (sublist, offset) position it was inserted at.
+    ///
+    /// If the sublist is full, it is split before inserting rather than after. Where possible
+    /// the split is made at the insertion point, so the value is pushed onto the end of the
+    /// first half without shifting anything.
+    fn insert(&mut self, new_val: T) -> (usize, usize) {
+        self.chunks.ensure_sublist();
+        let mut comparisons = 0;
+        let mut cmp = |a: &T, b: &T| {
+            comparisons += 1;
+            a.cmp(b)
+        };
+        let i = find_list_of_lists(&self.chunks.lists, &new_val, &mut cmp);
+        let j = match self.chunks.lists[i].binary_search_by(|x| cmp(x, &new_val)) {
+            Ok(j) | Err(j) => j,
+        };
+        self.chunks.record_comparisons(comparisons);
+        self.insert_at((i, j), new_val)
+    }
+
+    /// Inserts a value at a (sublist, offset) position that keeps the list sorted, returning
+    /// where it ended up.
+    fn insert_at(&mut self, (i, j): (usize, usize), new_val: T) -> (usize, usize) {
+        #[cfg(feature = "check-ord")]
+        self.check_ord_at((i, j), &new_val);
+        self.chunks.bump_version();
+        let len = self.chunks.lists[i].len();
+        self.chunks.len += 1;
+
+        if !self.chunks.should_split(len + 1) {
+            self.chunks.record_shifts(len - j);
+            self.chunks.lists[i].insert(j, new_val);
+            return (i, j);
+        }
+
+        let at = if 0 < j
+            && j < len
+            && !self.chunks.should_merge(j)
+            && !self.chunks.should_merge(len - j)
+        {
+            j
+        } else {
+            len / 2
+        };
+        let new_list = self.chunks.lists[i].split_off(at);
+        self.chunks.lists.insert(i + 1, new_list);
+        self.chunks.record_splits(1);
+        if j <= at {
+            self.chunks.record_shifts(at - j);
+            self.chunks.lists[i].insert(j, new_val);
+            (i, j)
+        } else {
+            self.chunks.record_shifts(len - j);
+            self.chunks.lists[i + 1].insert(j - at, new_val);
+            (i + 1, j - at)
+        }
+    }
+
+    /// Panics if the comparisons between a new value and the elements it would be inserted
+    /// between break the `Ord` contract, rather than letting the list silently fall out of
+    /// order. Only the neighbours are checked, so a broken `Ord` may still go unnoticed.
+    #[cfg(feature = "check-ord")]
+    fn check_ord_at(&self, (i, j): (usize, usize), new_val: &T) {
+        let name = std::any::type_name::<T>();
+        assert!(
+            new_val.cmp(new_val) == Ordering::Equal,
+            "inconsistent Ord for {}: a value is not equal to itself",
+            name
+        );
+        let prev = match j.checked_sub(1) {
+            Some(j) => self.chunks.lists[i].get(j),
+            None => i.checked_sub(1).and_then(|i| self.chunks.lists[i].last()),
+        };
+        let next = match self.chunks.lists[i].get(j) {
+            Some(next) => Some(next),
+            None => self.chunks.lists.get(i + 1).and_then(|list| list.first()),
+        };
+
+        for (neighbour, side) in prev
+            .map(|x| (x, "before"))
+            .into_iter()
+            .chain(next.map(|x| (x, "after")))
+        {
+            assert!(
+                new_val.cmp(neighbour) == neighbour.cmp(new_val).reverse(),
+                "inconsistent Ord for {}: a.cmp(b) is not the reverse of b.cmp(a) for a new value \
+                 and the element {} its insertion point",
+                name,
+                side
+            );
+        }
+        if let Some(prev) = prev {
+            assert!(
+                prev <= new_val,
+                "inconsistent Ord for {}: a new value sorted after an element that is greater than \
+                 it",
+                name
+            );
+        }
+        if let Some(next) = next {
+            assert!(
+                new_val <= next,
+                "inconsistent Ord for {}: a new value sorted before an element that is less than it",
+                name
+            );
+        }
+        if let (Some(prev), Some(next)) = (prev, next) {
+            assert!(
+                prev <= next,
+                "inconsistent Ord for {}: comparisons are not transitive around a new value",
+                name
+            );
+        }
+    }
+
+    /// Returns the element equal to `val`, inserting the result of `f` if there isn't one.
+    ///
+    /// Only one search is made. `f` must return a value equal to `val`; this is checked in debug
+    /// builds.
+    pub fn get_or_insert_with<F: FnOnce() -> T>(&mut self, val: &T, f: F) -> &T {
+        self.chunks.ensure_sublist();
+        let (i, j) = self.bisect_left_indices(val);
+        if self.chunks.get_indices((i, j)) == Some(val) {
+            return &self.chunks.lists[i][j];
+        }
+
+        let new_val = f();
+        debug_assert!(
+            new_val == *val,
+            "get_or_insert_with was given a function returning a different value"
+        );
+        let (i, j) = self.insert_at((i, j), new_val);
+        &self.chunks.lists[i][j]
+    }
+
+    /// Adds a value, or if there is already an element equal to it, puts the value in that
+    /// element's place and returns the element, like `BTreeSet::replace`.
+    ///
+    /// This is for when `Ord` only looks at part of a value, such as a key. If there are several
+    /// equal elements, the first of them is replaced.
+    pub fn replace(&mut self, val: T) -> Option<T> {
+        self.chunks.ensure_sublist();
+        let (i, j) = self.bisect_left_indices(&val);
+        if self.chunks.get_indices((i, j)) == Some(&val) {
+            self.chunks.bump_version();
+            return Some(std::mem::replace(&mut self.chunks.lists[i][j], val));
+        }
+        self.insert_at((i, j), val);
+        None
+    }
+
+    /// The position of the first element not less than `val`, as a (sublist, offset) pair.
+    fn bisect_left_indices(&self, val: &T) -> (usize, usize) {
+        self.bisect_indices(|x| x < val)
+    }
+
+    /// The position of the first element greater than `val`, as a (sublist, offset) pair.
+    fn bisect_right_indices(&self, val: &T) -> (usize, usize) {
+        self.bisect_indices(|x| x <= val)
+    }
+
+    /// Finds the first position whose element doesn't satisfy `pred`, which must be true for
+    /// some prefix of the list and false afterwards.
+    ///
+    /// A position past the end of the list is the end of the last sublist.
+    fn bisect_indices<F: Fn(&T) -> bool>(&self, pred: F) -> (usize, usize) {
+        let comparisons = Cell::new(0);
+        let pred = |x: &T| {
+            comparisons.set(comparisons.get() + 1);
+            pred(x)
+        };
+        let i = self
+            .chunks
+            .lists
+            .partition_point(|list| list.last().is_some_and(&pred));
+        let rv = if i == self.chunks.lists.len() {
+            self.chunks.end_indices()
+        } else {
+            (i, self.chunks.lists[i].partition_point(&pred))
+        };
+        self.chunks.record_comparisons(comparisons.get());
+        rv
+    }
+
+    /// The element just before a (sublist, offset) position, if there is one.
+    fn before_indices(&self, (i, j): (usize, usize)) -> Option<&T> {
+        if j > 0 {
+            self.chunks.get_indices((i, j - 1))
+        } else if i > 0 {
+            self.chunks.lists[i - 1].last()
+        } else {
+            None
+        }
+    }
+
+    /// Iterates between two (sublist, offset) positions, yielding nothing if they're inverted.
+    fn range_between(&self, start: (usize, usize), end: (usize, usize)) -> Range<'_, T> {
+        let remaining = if start < end {
+            self.chunks.flat_index(end.0, end.1) - self.chunks.flat_index(start.0, start.1)
+        } else {
+            0
+        };
+        Range {
+            iter: self.chunks.iter_from_indices(start),
+            remaining,
+        }
+    }
+
+    /// Removes the elements between two (sublist, offset) positions, returning how many were
+    /// removed. Nothing is removed if the positions are inverted.
+    fn remove_between(&mut self, start: (usize, usize), end: (usize, usize)) -> usize {
+        if start >= end {
+            return 0;
+        }
+        self.chunks.bump_version();
+        let before = self.chunks.len;
+        let lists = &mut self.chunks.lists[start.0..=end.0];
+        if let [list] = lists {
+            list.drain(start.1..end.1);
+        } else if let [first, middle @ .., last] = lists {
+            first.truncate(start.1);
+            middle.iter_mut().for_each(Vec::clear);
+            last.drain(..end.1);
+        }
+        self.chunks.normalize();
+        before - self.chunks.len
+    }
+
+    /// Removes the elements between two (sublist, offset) positions, returning them as a run of
+    /// sorted vectors. Nothing is removed if the positions are inverted.
+    fn drain_between(&mut self, start: (usize, usize), end: (usize, usize)) -> Vec<Vec<T>> {
+        if start >= end {
+            return Vec::new();
+        }
+        self.chunks.bump_version();
+        let lists = &mut self.chunks.lists;
+        let drained = if start.0 == end.0 {
+            vec![lists[start.0].drain(start.1..end.1).collect()]
+        } else {
+            let last = lists[end.0].drain(..end.1).collect();
+            let mut drained = vec![lists[start.0].split_off(start.1)];
+            drained.extend(lists.drain(start.0 + 1..end.0));
+            drained.push(last);
+            drained
+        };
+        self.chunks.normalize();
+        drained
+    }
+
+    /// Resolves a range of values to the (sublist, offset) positions of its first element and
+    /// one past its last element.
+    ///
+    /// If the range is inverted, the end position may come before the start position.
+    fn range_indices<R: RangeBounds<T>>(&self, range: &R) -> ((usize, usize), (usize, usize)) {
+        let start = match range.start_bound() {
+            Included(x) => self.bisect_left_indices(x),
+            Excluded(x) => self.bisect_right_indices(x),
+            Unbounded => (0, 0),
+        };
+        let end = match range.end_bound() {
+            Included(x) => self.bisect_right_indices(x),
+            Excluded(x) => self.bisect_left_indices(x),
+            Unbounded => self.chunks.end_indices(),
+        };
+        (start, end)
+    }
+
+    /// Replaces the contents of the list with a sorted vector, split into evenly sized sublists.
+    pub(crate) fn fill_sorted(&mut self, vals: Vec<T>) {
+        self.chunks.bump_version();
+        self.chunks.fill(vals);
+    }
+
+    /// Replaces the contents of the list with clones of a sorted slice.
+    pub(crate) fn fill_sorted_from_slice(&mut self, vals: &[T])
+    where
+        T: Clone,
+    {
+        debug_assert!(vals.is_sorted());
+        self.chunks.bump_version();
+        self.chunks.fill_from_slice(vals);
+    }
+
+    /// Re-sorts the list after its elements may have been modified in place.
+    ///
+    /// Sublists that are still sorted are left alone. If the sublists are out of order relative
+    /// to each other, the whole list is sorted, which is fast since it is made of sorted runs.
+    fn restore_order(&mut self) {
+        self.chunks.bump_version();
+        for list in &mut self.chunks.lists {
+            if !list.is_sorted() {
+                list.sort();
+            }
+        }
+
+        let in_order = self
+            .chunks
+            .lists
+            .windows(2)
+            .all(|pair| pair[0].last() <= pair[1].first());
+        if !in_order {
+            let mut vals = Vec::with_capacity(self.chunks.len);
+            for mut list in std::mem::take(&mut self.chunks.lists) {
+                vals.append(&mut list);
+            }
+            vals.sort();
+            self.fill_sorted(vals);
+        }
+    }
+
+    /// Creates a list holding clones of the values in a slice.
+    ///
+    /// The slice is copied straight into sublists if it is already sorted, and otherwise sorted
+    /// once as a whole, rather than adding the values one at a time.
+    pub fn from_slice(vals: &[T]) -> Self
+    where
+        T: Clone,
+    {
+        let mut list = Self::new();
+        list.extend_from_slice(vals);
+        list
+    }
+
+    /// Adds clones of the values in a slice, by sorting a copy of it and merging that in.
+    pub fn extend_from_slice(&mut self, vals: &[T])
+    where
+        T: Clone,
+    {
+        if !self.is_empty() {
+            let mut vals = vals.to_vec();
+            vals.sort();
+            self.merge_iter(vals);
+        } else if vals.is_sorted() {
+            self.fill_sorted_from_slice(vals);
+        } else {
+            let mut vals = vals.to_vec();
+            vals.sort();
+            self.fill_sorted(vals);
+        }
+    }
+
+    /// Creates a list of the distinct values of an iterator.
+    ///
+    /// Duplicates are dropped as the values are sorted, in batches, rather than being collected
+    /// and removed afterwards, so the memory used is proportional to the number of distinct
+    /// values.
+    pub fn from_iter_dedup<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.fill_sorted(sorted_distinct(iter));
+        list
+    }
+
+    /// Adds the distinct values of an iterator that aren't already in the list.
+    ///
+    /// Like `from_iter_dedup`, duplicates among the new values are dropped as they are sorted.
+    /// Duplicates already in the list are left alone.
+    pub fn add_all_dedup<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut vals = sorted_distinct(iter);
+        let mut existing = self.iter().peekable();
+        vals.retain(|x| {
+            while existing.next_if(|y| *y < x).is_some() {}
+            existing.peek() != Some(&x)
+        });
+        self.merge_iter(vals);
+    }
+
+    /// Merges values from an iterator, which must yield them in sorted order, into the list.
+    ///
+    /// This is a single pass over the sublists, so it is much faster than adding the values one
+    /// at a time when there are many of them. Unsorted input is detected in debug builds.
+    pub fn merge_iter<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut vals = iter.into_iter().peekable();
+        if vals.peek().is_none() {
+            return;
+        }
+
+        self.chunks.bump_version();
+        self.chunks.ensure_sublist();
+        let last = self.chunks.lists.len() - 1;
+        for (i, list) in self.chunks.lists.iter_mut().enumerate() {
+            let mut taken = Vec::new();
+            while let Some(x) = vals.next_if(|x| i == last || Some(x) <= list.last()) {
+                taken.push(x);
+            }
+            debug_assert!(taken.is_sorted(), "merge_iter was given unsorted values");
+            merge_sorted(list, taken);
+        }
+        self.chunks.normalize();
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.chunks.first()
+    }
+
+    /// Iterates over the elements within a range of values, in order, like `BTreeSet::range`.
+    ///
+    /// The start is found by binary search, so nothing before it is visited.
+    ///
+    /// ```
+    /// use sorted_collections::SortedList;
+    /// let list: SortedList<u32> = (0..100).collect();
+    /// assert!(list.range(10..13).eq([10, 11, 12].iter()));
+    /// assert_eq!(3, list.range(..=2).count());
+    /// ```
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Range<'_, T> {
+        let (start, end) = self.range_indices(&range);
+        self.range_between(start, end)
+    }
+
+    /// Iterates from the first element not less than `start` to the end of the list, like
+    /// `range(start..)` but without needing an owned bound.
+    ///
+    /// ```
+    /// use sorted_collections::SortedList;
+    /// let words: SortedList<String> = "kiwi fig apple lime".split(' ').map(String::from).collect();
+    /// assert!(words.iter_from(&"g".to_string()).eq(["kiwi", "lime"].iter()));
+    /// ```
+    pub fn iter_from(&self, start: &T) -> Range<'_, T> {
+        self.range_between(self.bisect_left_indices(start), self.chunks.end_indices())
+    }
+
+    /// The element at position `i`, or `None` if it is out of bounds.
+    pub fn get(&self, i: usize) -> Option<&T> {
+        self.chunks
+            .position_indices(i)
+            .map(|(i, j)| &self.chunks.lists[i][j])
+    }
+
+    /// The `k`th smallest element, counting from zero, or `None` if there are no more than `k`
+    /// elements. This is the same as `get(k)`.
+    pub fn kth(&self, k: usize) -> Option<&T> {
+        self.get(k)
+    }
+
+    /// The middle element, or the lower of the two middle elements if the length is even.
+    pub fn median(&self) -> Option<&T> {
+        self.get(self.len().checked_sub(1)? / 2)
+    }
+
+    /// The smallest element that at least `p` percent of the elements are less than or equal
+    /// to, by the nearest-rank method, or `None` if the list is empty.
+    ///
+    /// The 0th percentile is the first element and the 100th is the last. Panics if `p` is not
+    /// between 0 and 100.
+    ///
+    /// ```
+    /// use sorted_collections::SortedList;
+    /// let latencies: SortedList<u32> = (1..=200).collect();
+    /// assert_eq!(Some(&100), latencies.percentile(50.0));
+    /// assert_eq!(Some(&198), latencies.percentile(99.0));
+    /// assert_eq!(Some(&1), latencies.percentile(0.0));
+    /// ```
+    pub fn percentile(&self, p: f64) -> Option<&T> {
+        assert!(
+            (0.0..=100.0).contains(&p),
+            "percentile {} is not between 0 and 100",
+            p
+        );
+        let rank = (p / 100.0 * self.len() as f64).ceil() as usize;
+        self.get(rank.saturating_sub(1))
+    }
+
+    /// Returns a reference to the last (maximum) value in the list.
+    pub fn last(&self) -> Option<&T> {
+        self.chunks.last()
+    }
+
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        self.chunks.bump_version();
+        self.chunks.last_mut()
+    }
+
+    pub fn pop_first(&mut self) -> Option<T> {
+        self.chunks.bump_version();
+        self.chunks.pop_first()
+    }
+
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.chunks.bump_version();
+        self.chunks.pop_last()
+    }
+
+    /// Adds `val` and then removes and returns the smallest value, like Python's
+    /// `heapq.heappushpop`.
+    ///
+    /// If `val` lands in the first sublist, the sublist is shifted in place without any splitting
+    /// or joining.
+    pub fn push_pop_first(&mut self, val: T) -> T {
+        match self.first() {
+            Some(first) if *first < val => {}
+            _ => return val,
+        }
+
+        self.chunks.bump_version();
+        let list = &mut self.chunks.lists[0];
+        if Some(&val) <= list.last() {
+            let p = list.partition_point(|x| *x <= val);
+            let rv = std::mem::replace(&mut list[0], val);
+            list[..p].rotate_left(1);
+            rv
+        } else {
+            self.add(val);
+            self.pop_first().unwrap()
+        }
+    }
+
+    /// Adds `val` and then removes and returns the largest value.
+    ///
+    /// If `val` lands in the last sublist, the sublist is shifted in place without any splitting
+    /// or joining.
+    pub fn push_pop_last(&mut self, val: T) -> T {
+        match self.chunks.lists.last().and_then(|list| list.last()) {
+            Some(last) if *last > val => {}
+            _ => return val,
+        }
+
+        self.chunks.bump_version();
+        let list = self.chunks.lists.last_mut().unwrap();
+        if Some(&val) >= list.first() {
+            let p = list.partition_point(|x| *x < val);
+            let end = list.len() - 1;
+            let rv = std::mem::replace(&mut list[end], val);
+            list[p..].rotate_right(1);
+            rv
+        } else {
+            self.add(val);
+            self.pop_last().unwrap()
+        }
+    }
+
+    /// Removes the smallest value and then adds `val`, like Python's `heapq.heapreplace`.
+    pub fn replace_first(&mut self, val: T) -> Option<T> {
+        let rv = self.pop_first();
+        self.add(val);
+        rv
+    }
+
+    /// Removes the largest value and then adds `val`.
+    pub fn replace_last(&mut self, val: T) -> Option<T> {
+        let rv = self.pop_last();
+        self.add(val);
+        rv
+    }
+
+    pub fn len(&self) -> usize {
+        self.chunks.len
+    }
+
+    /// A counter that increases whenever the elements may have changed, so that anything
+    /// derived from the list can tell cheaply whether it is stale.
+    ///
+    /// It is bumped by every method that can add, remove or modify elements, including those
+    /// that hand out mutable references, whether or not anything is changed through them.
+    /// Moving elements between sublists without changing them, as `rebalance` does, leaves it
+    /// alone. Each list counts separately, starting from zero.
+    pub fn version(&self) -> u64 {
+        self.chunks.version
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.len == 0
+    }
+
+    /// The element `k` places from the end, so that `get_from_end(0)` is the largest, or `None`
+    /// if there are `k` or fewer elements.
+    ///
+    /// The sublists are walked from the back, so elements near the end are found quickly.
+    pub fn get_from_end(&self, k: usize) -> Option<&T> {
+        self.chunks
+            .position_from_end_indices(k)
+            .map(|(i, j)| &self.chunks.lists[i][j])
+    }
+
+    /// The element `k` places from the end, as in `get_from_end`.
+    ///
+    /// Panics if there are `k` or fewer elements.
+    pub fn index_from_end(&self, k: usize) -> &T {
+        match self.get_from_end(k) {
+            Some(val) => val,
+            None => panic!(
+                "index {} from the end out of range for list of length {}",
+                k,
+                self.len()
+            ),
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.chunks.iter()
+    }
+
+    /// Returns an element chosen uniformly at random, or `None` if the list is empty.
+    ///
+    /// Requires the `rand` feature.
+    #[cfg(feature = "rand")]
+    pub fn choose<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&T> {
+        if self.is_empty() {
+            return None;
+        }
+        let (i, j) = self.chunks.position_indices(rng.gen_range(0, self.len()))?;
+        Some(&self.chunks.lists[i][j])
+    }
+
+    /// Keeps only the elements for which `pred` returns true.
+    ///
+    /// The sublists are filtered in parallel on the rayon thread pool, and then rebalanced once.
+    /// Requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn par_retain<F>(&mut self, pred: F)
+    where
+        T: Send,
+        F: Fn(&T) -> bool + Sync,
+    {
+        self.chunks.bump_version();
+        self.chunks
+            .lists
+            .par_iter_mut()
+            .for_each(|list| list.retain(&pred));
+        self.chunks.normalize();
+    }
+
+    /// Calls `f` on each sublist in parallel on the rayon thread pool.
+    ///
+    /// Elements may be modified in any way; afterwards the list is re-sorted as when an
+    /// `iter_mut_guarded` guard is dropped. Requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn par_for_each_chunk<F>(&mut self, f: F)
+    where
+        T: Send,
+        F: Fn(&mut [T]) + Sync,
+    {
+        self.chunks.lists.par_iter_mut().for_each(|list| f(list));
+        self.restore_order();
+    }
+
+    /// Iterates over each distinct value once, in order.
+    ///
+    /// Runs of equal values are skipped with a binary search rather than by visiting each copy.
+    pub fn iter_distinct(&self) -> Distinct<'_, T> {
+        Distinct {
+            list: self,
+            position: (0, 0),
+        }
+    }
+
+    /// Iterates over the sublists as sorted slices, in order.
+    ///
+    /// Concatenating the slices gives the whole list. Empty slices are never yielded.
+    pub fn as_slices(&self) -> impl Iterator<Item = &[T]> {
+        self.chunks
+            .lists
+            .iter()
+            .map(Vec::as_slice)
+            .filter(|slice| !slice.is_empty())
+    }
+
+    /// Builds a list from sorted chunks, such as per-thread sorted runs, without flattening them.
+    ///
+    /// The chunks must be sorted when concatenated. If they aren't, they are handed back in the
+    /// error. Empty chunks are dropped and chunks outside the growth policy's size bounds are split
+    /// or joined, so the chunks of the list may not match the ones given.
+    pub fn from_chunks(chunks: Vec<Vec<T>>) -> Result<Self, UnsortedChunks<T>> {
+        if chunks.iter().flatten().is_sorted() {
+            Ok(Self::from_chunks_unchecked(chunks))
+        } else {
+            Err(UnsortedChunks(chunks))
+        }
+    }
+
+    /// Builds a list from chunks that are already known to be sorted when concatenated.
+    ///
+    /// Chunk sizes are still normalized as in `from_chunks`. Unsorted input is detected in debug
+    /// builds.
+    pub fn from_chunks_unchecked(chunks: Vec<Vec<T>>) -> Self {
+        debug_assert!(
+            chunks.iter().flatten().is_sorted(),
+            "from_chunks_unchecked was given unsorted chunks"
+        );
+        let mut list = Self::new();
+        list.chunks.lists = chunks;
+        list.chunks.normalize();
+        list
+    }
+
+    /// Builds a list from a vector that is already sorted, splitting it straight into sublists
+    /// of the growth policy's chunk size. This is `O(n)`, where collecting would sort first.
+    ///
+    /// Unsorted input is detected in debug builds; in release builds it leaves the list out of
+    /// order.
+    pub fn from_sorted_vec(vals: Vec<T>) -> Self {
+        debug_assert!(
+            vals.is_sorted(),
+            "from_sorted_vec was given unsorted values"
+        );
+        let mut list = Self::new();
+        list.fill_sorted(vals);
+        list
+    }
+
+    /// Builds a list from an iterator that yields its values in sorted order, as
+    /// `from_sorted_vec` does. `SortedIterator::collect_sorted` does the same for iterators
+    /// that are known to be sorted.
+    pub fn from_sorted_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_sorted_vec(iter.into_iter().collect())
+    }
+
+    /// Divides the list into `n` lists of roughly equal length covering consecutive ranges of
+    /// values, for processing in parallel. Runs of equal values may be divided between
+    /// neighbouring parts.
+    ///
+    /// Sublists are moved whole rather than copied, so the lengths are only as even as the
+    /// sublists allow, and some parts are empty if the list has fewer than `n` sublists. Each part
+    /// keeps this list's growth policy. Panics if `n` is zero.
+    pub fn split_into(self, n: usize) -> Vec<SortedList<T>> {
+        self.chunks
+            .split_into(n)
+            .into_iter()
+            .map(|chunks| SortedList { chunks })
+            .collect()
+    }
+
+    /// Rewrites the sublists to the policy's chunk size after a long run of mixed insertions and
+    /// removals has left them uneven, releasing any spare capacity.
+    ///
+    /// Takes time proportional to the length. `fragmentation` gives a hint for when it is worth
+    /// it.
+    pub fn rebalance(&mut self) {
+        self.chunks.rebalance();
+    }
+
+    /// The fraction of sublists that `rebalance` would remove, from zero when they are all as
+    /// full as the growth policy allows to just under one.
+    pub fn fragmentation(&self) -> f64 {
+        self.chunks.fragmentation()
+    }
+
+    /// The number of elements the list has room for without allocating, summed over its
+    /// sublists and any spare ones kept by `reserve` or `clear_keep_capacity`.
+    ///
+    /// Sublists are split before they fill up, so this is only a bound on how many elements
+    /// fit before the next allocation when the list isn't being added to at its end.
+    pub fn capacity(&self) -> usize {
+        self.chunks.capacity()
+    }
+
+    /// Releases the memory the list holds beyond its elements: each sublist is trimmed to its
+    /// length, spare sublists are freed, and so is unused room in the vector of sublists.
+    ///
+    /// Sublists keep their lengths, so this doesn't help with memory lost to many half-empty
+    /// sublists; `rebalance` rewrites those.
+    pub fn shrink_to_fit(&mut self) {
+        self.chunks.shrink_to_fit();
+    }
+
+    /// Removes the elements at the given indices, returning them in index order. The indices
+    /// may be in any order, and repeats are ignored.
+    ///
+    /// All of the removals are made in a single pass over the sublists, which are then
+    /// rebalanced once, so this is much faster than removing the elements one at a time. Panics if
+    /// any index is out of bounds.
+    pub fn remove_indices<I: IntoIterator<Item = usize>>(&mut self, indices: I) -> Vec<T> {
+        self.chunks.bump_version();
+        self.chunks.remove_positions(indices.into_iter().collect())
+    }
+
+    /// Removes the elements at a range of positions.
+    ///
+    /// Sublists that fall entirely inside the range are dropped whole and the two at its ends
+    /// are trimmed, then the sublists are rebalanced once. Nothing is shifted more than once,
+    /// unlike removing the elements one at a time. Panics if the range is inverted or out of
+    /// bounds.
+    ///
+    /// ```
+    /// use sorted_collections::SortedList;
+    /// let mut list: SortedList<u32> = (0..10_000).collect();
+    /// list.remove_range(10..9990);
+    /// assert_eq!(20, list.len());
+    /// assert_eq!(Some(&9990), list.get(10));
+    /// ```
+    pub fn remove_range<R: RangeBounds<usize>>(&mut self, range: R) {
+        let range = self.chunks.index_range(range);
+        let [start, end] = [range.start, range.end].map(|i| {
+            self.chunks
+                .position_indices(i)
+                .unwrap_or_else(|| self.chunks.end_indices())
+        });
+        self.remove_between(start, end);
+    }
+
+    /// Removes every element, yielding them in order.
+    ///
+    /// When the iterator is dropped, the list is left with a single empty sublist that keeps the
+    /// allocation of the first one, ready to be refilled. Elements that haven't been yielded by
+    /// then are dropped.
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        self.chunks.bump_version();
+        let mut lists = std::mem::take(&mut self.chunks.lists);
+        let remaining = std::mem::replace(&mut self.chunks.len, 0);
+        // Reversed, so that each sublist can be emptied from the front with `pop`.
+        for list in &mut lists {
+            list.reverse();
+        }
+        Drain {
+            list: self,
+            lists,
+            current: 0,
+            remaining,
         }
     }
 
-    pub fn contains(&self, val: &T) -> bool {
-        debug_assert!(!self.lists.is_empty());
+    /// Removes every element, freeing the sublists.
+    pub fn clear(&mut self) {
+        self.chunks.bump_version();
+        self.chunks.clear(false);
+    }
 
-        self.lists.iter().any(|list| list.contains(val))
+    /// Removes every element, keeping the sublists' allocations to be reused as the list is
+    /// refilled, instead of allocating new ones. They are held until the list is dropped or
+    /// `clear` is called.
+    pub fn clear_keep_capacity(&mut self) {
+        self.chunks.bump_version();
+        self.chunks.clear(true);
     }
 
-    pub fn add(&mut self, new_val: T) {
-        let i_changed = insert_list_of_lists(&mut self.lists, new_val);
-        self.len += 1;
-        self.expand(i_changed);
+    /// Moves all of the elements into a new list, leaving this one empty.
+    ///
+    /// Unlike `std::mem::take`, both lists keep this list's growth policy.
+    pub fn take_all(&mut self) -> SortedList<T> {
+        self.chunks.bump_version();
+        SortedList {
+            chunks: self.chunks.take(),
+        }
     }
 
-    /// Splits sublists that are more than double the load level.
-    /// Updates the index when the sublist length is less than double the load
-    /// level. This requires incrementing the nodes in a traversal from the
-    /// leaf node to the root. For an example traversal see self._loc.
-    fn expand(&mut self, i: usize) {
-        // >= because otherwise contract can fail... better solution for this?
-        if self.lists[i].len() >= 2 * self.load_factor {
-            self.unchecked_expand(i)
+    /// Swaps the elements of `other` in for the current ones, which are returned.
+    ///
+    /// Both lists keep this list's growth policy, so `other`'s sublists are resized to suit it
+    /// if necessary.
+    pub fn replace_contents(&mut self, other: SortedList<T>) -> SortedList<T> {
+        let old = self.take_all();
+        self.chunks.lists = other.chunks.lists;
+        self.chunks.normalize();
+        old
+    }
+
+    /// Takes the list apart into its sorted sublists, in order, without copying the elements.
+    ///
+    /// Concatenating the chunks gives the whole list. Empty chunks are never returned.
+    pub fn into_chunks(self) -> Vec<Vec<T>> {
+        let mut lists = self.chunks.lists;
+        lists.retain(|list| !list.is_empty());
+        lists
+    }
+
+    /// Concatenates the sublists into one sorted vector.
+    ///
+    /// The first sublist is grown to the full length with a single reallocation and the others
+    /// are moved onto the end of it.
+    pub fn into_vec(self) -> Vec<T> {
+        let len = self.chunks.len;
+        let mut lists = self.chunks.lists.into_iter();
+        let mut vec = lists.next().unwrap_or_default();
+        vec.reserve_exact(len - vec.len());
+        for mut list in lists {
+            vec.append(&mut list);
         }
+        vec
     }
 
-    fn unchecked_expand(&mut self, i: usize) {
-        let new_list = {
-            let inner = &mut self.lists[i];
-            let mid = inner.len() / 2;
-            inner.split_off(mid)
-        };
+    /// Allows the elements to be modified in place through the returned guard.
+    ///
+    /// When the guard is dropped, the list is re-sorted if any values were moved out of order.
+    /// Only sublists that became unsorted are sorted, unless values moved between sublists.
+    ///
+    /// ```
+    /// use sorted_collections::SortedList;
+    /// let mut list: SortedList<i32> = vec![1, 2, 3, 4].into_iter().collect();
+    /// for x in list.iter_mut_guarded().iter_mut() {
+    ///     *x = 10 - *x * *x;
+    /// }
+    /// assert_eq!(vec![-6, 1, 6, 9], list.into_iter().collect::<Vec<_>>());
+    /// ```
+    pub fn iter_mut_guarded(&mut self) -> IterMutGuard<'_, T> {
+        IterMutGuard { list: self }
+    }
+
+    /// Applies an order-preserving function to every element, without re-sorting.
+    ///
+    /// `f` must be monotonic: if `a <= b` then `f(a) <= f(b)`. This is checked in debug builds.
+    pub fn map_monotonic<U: Ord, F: Fn(T) -> U>(self, f: F) -> SortedList<U> {
+        let lists: Vec<Vec<U>> = self
+            .chunks
+            .lists
+            .into_iter()
+            .map(|list| list.into_iter().map(&f).collect())
+            .collect();
+        debug_assert!(
+            lists.iter().flatten().is_sorted(),
+            "map_monotonic was given a function that doesn't preserve order"
+        );
+
+        let mut chunks = ChunkedStorage::new();
+        chunks.lists = lists;
+        chunks.policy = self.chunks.policy;
+        chunks.len = self.chunks.len;
+        SortedList { chunks }
+    }
+
+    /// Counts the elements falling between each pair of consecutive boundaries.
+    ///
+    /// `boundaries` must be sorted. The buckets are `..b[0]`, `b[0]..b[1]`, and so on up to
+    /// `b[last]..`, so there is one more count than there are boundaries. Each bucket is found
+    /// with a binary search rather than by visiting its elements.
+    pub fn histogram(&self, boundaries: &[T]) -> Vec<usize> {
+        self.histogram_iter(boundaries)
+            .map(|(_, count)| count)
+            .collect()
+    }
+
+    /// Iterates over the buckets of `histogram` along with their bounds.
+    pub fn histogram_iter<'a>(
+        &'a self,
+        boundaries: &'a [T],
+    ) -> impl Iterator<Item = ((Bound<&'a T>, Bound<&'a T>), usize)> + 'a {
+        debug_assert!(
+            boundaries.is_sorted(),
+            "histogram boundaries must be sorted"
+        );
+
+        let lows = Some(Unbounded)
+            .into_iter()
+            .chain(boundaries.iter().map(Included));
+        let highs = boundaries.iter().map(Excluded).chain(Some(Unbounded));
+        let ranks = boundaries
+            .iter()
+            .map(move |b| {
+                let (i, j) = self.bisect_left_indices(b);
+                self.chunks.flat_index(i, j)
+            })
+            .chain(Some(self.chunks.len));
+        lows.zip(highs).zip(ranks).scan(0, |prev, (bounds, rank)| {
+            let count = rank - *prev;
+            *prev = rank;
+            Some((bounds, count))
+        })
+    }
+
+    /// Returns the most frequent value and how many times it occurs.
+    ///
+    /// If several values are equally frequent, the smallest is returned.
+    pub fn mode(&self) -> Option<(&T, usize)> {
+        self.runs()
+            .fold(None, |best: Option<(&T, usize)>, (val, count)| match best {
+                Some((_, best_count)) if best_count >= count => best,
+                _ => Some((val, count)),
+            })
+    }
 
-        self.lists.insert(i + 1, new_list);
+    /// Returns up to `k` of the most frequent values with their counts, most frequent first.
+    ///
+    /// Values that are equally frequent are ordered smallest first.
+    pub fn most_common(&self, k: usize) -> Vec<(&T, usize)> {
+        let mut runs: Vec<(&T, usize)> = self.runs().collect();
+        // Stable, so ties keep their sorted order.
+        runs.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        runs.truncate(k);
+        runs
     }
 
-    fn contract(&mut self, i: usize) {
-        if self.lists.len() > 1 && self.lists[i].len() < self.load_factor / 2 {
-            self.unchecked_contract(i)
+    /// Binary searches the list with a comparator function, as `slice::binary_search_by`.
+    ///
+    /// The comparator should return whether an element is `Less`, `Equal` or `Greater` than the
+    /// target, and must be consistent with the sort order of the list. Returns `Ok` with the
+    /// index of a matching element, or `Err` with the index where a matching element could be
+    /// inserted while keeping the list sorted.
+    pub fn binary_search_by<F>(&self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        let i = self
+            .chunks
+            .lists
+            .partition_point(|list| list.last().is_some_and(|x| f(x) == Ordering::Less));
+        if i == self.chunks.lists.len() {
+            return Err(self.chunks.len);
         }
+
+        let offset = self.chunks.flat_index(i, 0);
+        self.chunks.lists[i]
+            .binary_search_by(f)
+            .map(|j| offset + j)
+            .map_err(|j| offset + j)
+    }
+
+    /// Binary searches the list for an element whose key, as extracted by `f`, equals `b`.
+    ///
+    /// The list must be sorted by the key. See `binary_search_by` for the return value.
+    pub fn binary_search_by_key<B, F>(&self, b: &B, mut f: F) -> Result<usize, usize>
+    where
+        B: Ord,
+        F: FnMut(&T) -> B,
+    {
+        self.binary_search_by(|x| f(x).cmp(b))
+    }
+
+    /// Iterates over the elements whose key, as extracted by `f`, equals `b`.
+    ///
+    /// The list must be sorted by the key, as it is when the key is a prefix of the element's
+    /// ordering (such as a field compared first).
+    pub fn range_by_key<B, F>(&self, b: &B, f: F) -> Range<'_, T>
+    where
+        B: Ord,
+        F: Fn(&T) -> B,
+    {
+        let start = self.bisect_indices(|x| f(x) < *b);
+        let end = self.bisect_indices(|x| f(x) <= *b);
+        self.range_between(start, end)
     }
 
-    // TODO: this can make lists that are too big.
-    /// Contracts with the nearest list.
-    fn unchecked_contract(&mut self, i: usize) {
-        debug_assert!(self.lists.len() > 1);
-        let (low, high) = match i {
-            0 => (0, 1),
-            i if i == self.lists.len() => (self.lists.len() - 2, self.lists.len() - 1),
-            i => {
-                let other_list: usize = if self.lists[i - 1].len() < self.lists[i + 1].len() {
-                    i - 1
+    /// Combines two lists into one, keeping every element, in `O(n + m)` time. The result has
+    /// this list's growth policy, and equal elements from this list come first.
+    ///
+    /// The sublists of both are walked together. Where a sublist doesn't overlap the other
+    /// list's current sublist it is adopted whole; elements are only moved one at a time where
+    /// the two overlap.
+    ///
+    /// ```
+    /// use sorted_collections::SortedList;
+    /// let old: SortedList<u32> = (0..1000).collect();
+    /// let new: SortedList<u32> = (500..1500).collect();
+    /// let merged = old.merge(new);
+    /// assert_eq!(2000, merged.len());
+    /// assert_eq!(2, merged.count(&700));
+    /// ```
+    pub fn merge(mut self, other: SortedList<T>) -> SortedList<T> {
+        self.chunks.len += other.chunks.len;
+        let chunk_size = self.chunks.chunk_size();
+        let mut left = std::mem::take(&mut self.chunks.lists).into_iter();
+        let mut right = other.chunks.lists.into_iter();
+        let mut a = Vec::new().into_iter();
+        let mut b = Vec::new().into_iter();
+        let mut lists = Vec::new();
+        let mut run = Vec::new();
+        loop {
+            if a.as_slice().is_empty() {
+                match left.next() {
+                    Some(list) => a = list.into_iter(),
+                    None => break,
+                }
+                continue;
+            }
+            if b.as_slice().is_empty() {
+                match right.next() {
+                    Some(list) => b = list.into_iter(),
+                    None => break,
+                }
+                continue;
+            }
+
+            let (xs, ys) = (a.as_slice(), b.as_slice());
+            if xs.last() <= ys.first() {
+                lists.push(std::mem::take(&mut run));
+                lists.push(std::mem::take(&mut a).collect());
+            } else if ys.last() < xs.first() {
+                lists.push(std::mem::take(&mut run));
+                lists.push(std::mem::take(&mut b).collect());
+            } else {
+                if run.len() == chunk_size {
+                    lists.push(std::mem::take(&mut run));
+                }
+                if ys[0] < xs[0] {
+                    run.extend(b.next());
                 } else {
-                    i + 1
-                };
-                if i < other_list {
-                    (i, other_list)
+                    run.extend(a.next());
+                }
+            }
+        }
+
+        // At most one side has anything left.
+        lists.push(run);
+        lists.push(a.collect());
+        lists.extend(left);
+        lists.push(b.collect());
+        lists.extend(right);
+        self.chunks.lists = lists;
+        self.chunks.bump_version();
+        self.chunks.normalize();
+        self
+    }
+
+    /// Combines many lists into one, keeping every element, with a k-way merge.
+    ///
+    /// This takes `O(n log k)` time for `k` lists with `n` elements in total.
+    pub fn union_all<I: IntoIterator<Item = SortedList<T>>>(lists: I) -> SortedList<T> {
+        let mut iters: Vec<IntoIter<T>> = Vec::new();
+        let mut len = 0;
+        for list in lists {
+            len += list.chunks.len;
+            iters.push(list.into_iter());
+        }
+
+        let mut heap = BinaryHeap::with_capacity(iters.len());
+        for (i, iter) in iters.iter_mut().enumerate() {
+            if let Some(x) = iter.next() {
+                heap.push(Reverse((x, i)));
+            }
+        }
+
+        let mut vals = Vec::with_capacity(len);
+        while let Some(Reverse((x, i))) = heap.pop() {
+            vals.push(x);
+            if let Some(next) = iters[i].next() {
+                heap.push(Reverse((next, i)));
+            }
+        }
+
+        let mut list = SortedList::new();
+        list.fill_sorted(vals);
+        list
+    }
+
+    /// Adds the values of `other` that are not already in the list.
+    ///
+    /// Lists are treated as multisets: afterwards, each value occurs as many times as it did in
+    /// whichever of the two lists had more copies of it.
+    pub fn union_with(&mut self, other: SortedList<T>) {
+        let mut other = other.into_iter().peekable();
+        let mut additions = Vec::new();
+        for x in self.iter() {
+            while let Some(y) = other.next_if(|y| y < x) {
+                additions.push(y);
+            }
+            other.next_if(|y| y == x);
+        }
+        additions.extend(other);
+        self.merge_iter(additions);
+    }
+
+    /// Removes the values that are not in `other`.
+    ///
+    /// Lists are treated as multisets: afterwards, each value occurs as many times as it did in
+    /// whichever of the two lists had fewer copies of it.
+    pub fn intersect_with(&mut self, other: &SortedList<T>) {
+        self.chunks.bump_version();
+        let mut other = other.iter().peekable();
+        for list in &mut self.chunks.lists {
+            list.retain(|x| {
+                while other.next_if(|y| *y < x).is_some() {}
+                other.next_if(|y| *y == x).is_some()
+            });
+        }
+        self.chunks.normalize();
+    }
+
+    /// Removes the values that are in `other`.
+    ///
+    /// Lists are treated as multisets: each copy of a value in `other` removes one copy of it
+    /// from this list.
+    pub fn difference_with(&mut self, other: &SortedList<T>) {
+        self.chunks.bump_version();
+        let mut other = other.iter().peekable();
+        for list in &mut self.chunks.lists {
+            list.retain(|x| {
+                while other.next_if(|y| *y < x).is_some() {}
+                other.next_if(|y| *y == x).is_none()
+            });
+        }
+        self.chunks.normalize();
+    }
+
+    /// The length that `intersect_with` would leave the list with, counted in one pass over both
+    /// lists without building anything.
+    pub fn intersection_len(&self, other: &SortedList<T>) -> usize {
+        let mut other = other.iter().peekable();
+        let mut count = 0;
+        for x in self.iter() {
+            while other.next_if(|y| *y < x).is_some() {}
+            if other.next_if(|y| *y == x).is_some() {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// The length that `union_with` would leave the list with.
+    pub fn union_len(&self, other: &SortedList<T>) -> usize {
+        self.len() + other.len() - self.intersection_len(other)
+    }
+
+    /// The Jaccard similarity of the two lists as multisets: the size of their intersection
+    /// divided by the size of their union. Two empty lists are considered identical.
+    pub fn jaccard(&self, other: &SortedList<T>) -> f64 {
+        let intersection = self.intersection_len(other);
+        let union = self.len() + other.len() - intersection;
+        if union == 0 {
+            1.0
+        } else {
+            intersection as f64 / union as f64
+        }
+    }
+
+    /// Removes all but the first of each run of equal elements, leaving each value once.
+    ///
+    /// This works through the sublists in place and then joins any that were left too small,
+    /// so it is cheaper than collecting the distinct values into a new list.
+    pub fn dedup(&mut self) {
+        self.dedup_by(|a, b| a == b);
+    }
+
+    /// Removes all but the first of each run of consecutive elements that `same_bucket`
+    /// considers equal.
+    ///
+    /// As with `Vec::dedup_by`, `same_bucket(a, b)` is passed the element being considered as
+    /// `a` and the last element kept as `b`. Only adjacent elements are compared, so the closure
+    /// should group elements in a way that is consistent with their order.
+    pub fn dedup_by<F: FnMut(&T, &T) -> bool>(&mut self, mut same_bucket: F) {
+        self.chunks.bump_version();
+        for i in 0..self.chunks.lists.len() {
+            let (before, after) = self.chunks.lists.split_at_mut(i);
+            if let Some(kept) = before.iter().rev().find_map(|list| list.last()) {
+                let n = after[0].iter().take_while(|x| same_bucket(x, kept)).count();
+                after[0].drain(..n);
+            }
+            after[0].dedup_by(|a, b| same_bucket(a, b));
+        }
+        self.chunks.normalize();
+    }
+
+    /// Removes all but the first of each run of consecutive elements with the same key.
+    pub fn dedup_by_key<K: PartialEq, F: FnMut(&T) -> K>(&mut self, mut key: F) {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    /// Returns the element nearest to `val`, or `None` if the list is empty.
+    ///
+    /// If two elements are equally near, the lower one is returned.
+    pub fn closest(&self, val: &T) -> Option<&T>
+    where
+        T: Clone + Sub<Output = T>,
+    {
+        match (self.find_le(val), self.find_ge(val)) {
+            (Some(low), Some(high)) => {
+                if high.clone() - val.clone() < val.clone() - low.clone() {
+                    Some(high)
                 } else {
-                    (other_list, i)
+                    Some(low)
                 }
             }
+            (low, high) => low.or(high),
+        }
+    }
+
+    /// Iterates over the `k` elements nearest to `val`, nearest first, walking outwards from
+    /// where `val` would be inserted. Fewer are yielded if the list is shorter than `k`.
+    ///
+    /// As with `closest`, of two equally near elements the lower one comes first.
+    ///
+    /// ```
+    /// use sorted_collections::SortedList;
+    /// let list: SortedList<i32> = vec![1, 4, 6, 7, 12].into_iter().collect();
+    /// assert_eq!(vec![&4, &6, &7], list.nearest_k(&5, 3).collect::<Vec<_>>());
+    /// ```
+    pub fn nearest_k(&self, val: &T, k: usize) -> NearestK<'_, T>
+    where
+        T: Clone + Sub<Output = T>,
+    {
+        let position = self.bisect_left_indices(val);
+        NearestK {
+            lists: &self.chunks.lists,
+            below: position,
+            above: position,
+            val: val.clone(),
+            remaining: k,
+        }
+    }
+
+    /// Whether some value occurs more than once, checked in one pass over the sublists.
+    pub fn has_duplicates(&self) -> bool {
+        let lists = &self.chunks.lists;
+        lists
+            .iter()
+            .any(|list| list.windows(2).any(|pair| pair[0] == pair[1]))
+            || lists
+                .windows(2)
+                .any(|pair| pair[0].last().is_some() && pair[0].last() == pair[1].first())
+    }
+
+    /// Whether each element is strictly greater than the one before, so that the list could
+    /// be used as a set.
+    pub fn is_strictly_increasing(&self) -> bool {
+        !self.has_duplicates()
+    }
+
+    /// Iterates over the differences between consecutive elements, each later element minus the
+    /// one before it. There is one fewer gap than there are elements.
+    ///
+    /// ```
+    /// use sorted_collections::SortedList;
+    /// let list: SortedList<u32> = vec![1, 2, 4, 8].into_iter().collect();
+    /// assert_eq!(vec![1, 2, 4], list.gaps().collect::<Vec<_>>());
+    /// ```
+    pub fn gaps(&self) -> impl Iterator<Item = T> + '_
+    where
+        T: Clone + Sub<Output = T>,
+    {
+        self.gaps_by(|low, high| high.clone() - low.clone())
+    }
+
+    /// Iterates over `f(low, high)` for each pair of consecutive elements, for types without a
+    /// `Sub` implementation or to measure the distance some other way.
+    pub fn gaps_by<'a, D, F>(&'a self, mut f: F) -> impl Iterator<Item = D> + 'a
+    where
+        F: FnMut(&T, &T) -> D + 'a,
+    {
+        self.iter()
+            .zip(self.iter().skip(1))
+            .map(move |(low, high)| f(low, high))
+    }
+
+    /// The pair of consecutive elements with the largest difference between them, or `None` if
+    /// there are fewer than two elements. If several pairs are equally far apart, the first is
+    /// returned.
+    pub fn max_gap(&self) -> Option<(&T, &T)>
+    where
+        T: Clone + Sub<Output = T>,
+    {
+        self.max_gap_by(|low, high| high.clone() - low.clone())
+    }
+
+    /// The pair of consecutive elements that are furthest apart by `distance`, as in `max_gap`.
+    ///
+    /// The distance only needs to be partially ordered, so it may be a float. Pairs whose
+    /// distance can't be compared with the largest so far are skipped.
+    pub fn max_gap_by<D, F>(&self, mut distance: F) -> Option<(&T, &T)>
+    where
+        D: PartialOrd,
+        F: FnMut(&T, &T) -> D,
+    {
+        let mut widest: Option<(D, (&T, &T))> = None;
+        for (low, high) in self.iter().zip(self.iter().skip(1)) {
+            let d = distance(low, high);
+            if widest.as_ref().is_none_or(|(max, _)| d > *max) {
+                widest = Some((d, (low, high)));
+            }
+        }
+        widest.map(|(_, pair)| pair)
+    }
+
+    fn runs(&self) -> Runs<'_, T> {
+        Runs {
+            iter: self.iter().peekable(),
+        }
+    }
+}
+
+/// Iterates over runs of equal values, yielding the first value of each run and its length.
+struct Runs<'a, T: 'a> {
+    iter: Peekable<Iter<'a, T>>,
+}
+
+impl<'a, T: Ord> Iterator for Runs<'a, T> {
+    type Item = (&'a T, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.iter.next()?;
+        let mut count = 1;
+        while self.iter.next_if(|x| *x == first).is_some() {
+            count += 1;
+        }
+        Some((first, count))
+    }
+}
+
+/// The error returned by `SortedList::from_chunks` when the chunks are out of order. It holds the
+/// chunks that were passed in.
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnsortedChunks<T>(pub Vec<Vec<T>>);
+
+impl<T> fmt::Display for UnsortedChunks<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "chunks are not sorted")
+    }
+}
+
+impl<T: fmt::Debug> Error for UnsortedChunks<T> {}
+
+/// Iterates over the distinct values of a `SortedList`, in order.
+///
+/// Created by `SortedList::iter_distinct`.
+#[derive(Debug)]
+pub struct Distinct<'a, T: Ord + 'a> {
+    list: &'a SortedList<T>,
+    position: (usize, usize),
+}
+
+impl<'a, T: Ord> Iterator for Distinct<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let (i, j) = self.position;
+        let lists = &self.list.chunks.lists;
+        let val = self.list.chunks.get_indices((i, j))?;
+        self.position = if lists[i].last() > Some(val) {
+            (i, j + lists[i][j..].partition_point(|x| x <= val))
+        } else {
+            let (i, j) = self.list.bisect_right_indices(val);
+            if j == lists[i].len() && i + 1 < lists.len() {
+                (i + 1, 0)
+            } else {
+                (i, j)
+            }
         };
+        Some(val)
+    }
+}
+
+impl<'a, T: Ord> FusedIterator for Distinct<'a, T> {}
+
+/// Removes and yields the elements of a `SortedList`, in order.
+///
+/// Created by `SortedList::drain`.
+#[derive(Debug)]
+pub struct Drain<'a, T: Ord + 'a> {
+    list: &'a mut SortedList<T>,
+    // Moved out of the list, so that it is simply empty if the iterator is leaked.
+    lists: Vec<Vec<T>>,
+    current: usize,
+    remaining: usize,
+}
+
+impl<'a, T: Ord> Iterator for Drain<'a, T> {
+    type Item = T;
 
-        let mut removed_list = self.lists.remove(high);
-        self.lists[low].append(&mut removed_list);
+    fn next(&mut self) -> Option<T> {
+        while let Some(list) = self.lists.get_mut(self.current) {
+            if let Some(val) = list.pop() {
+                self.remaining -= 1;
+                return Some(val);
+            }
+            self.current += 1;
+        }
+        None
     }
 
-    pub fn first(&self) -> Option<&T> {
-        self.lists.first().and_then(|x| x.first())
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
     }
+}
 
-    /// Returns a reference to the last (maximum) value in the list.
-    pub fn last(&mut self) -> Option<&T> {
-        self.lists.last().and_then(|x| x.last())
+impl<'a, T: Ord> ExactSizeIterator for Drain<'a, T> {}
+
+impl<'a, T: Ord> FusedIterator for Drain<'a, T> {}
+
+impl<'a, T: Ord> Drop for Drain<'a, T> {
+    fn drop(&mut self) {
+        self.lists.truncate(1);
+        if let Some(list) = self.lists.first_mut() {
+            list.clear();
+        }
+        self.list.chunks.lists = std::mem::take(&mut self.lists);
+        self.list.chunks.ensure_sublist();
     }
+}
 
-    pub fn last_mut(&mut self) -> Option<&mut T> {
-        self.lists.last_mut().and_then(|x| x.last_mut())
+/// Iterates over the elements of a `SortedList` nearest to a value, nearest first.
+///
+/// Created by `SortedList::nearest_k`.
+#[derive(Debug)]
+pub struct NearestK<'a, T: 'a> {
+    lists: &'a [Vec<T>],
+    // The position just after the next element below `val`.
+    below: (usize, usize),
+    // The position of the next element at or above `val`.
+    above: (usize, usize),
+    val: T,
+    remaining: usize,
+}
+
+impl<'a, T> NearestK<'a, T> {
+    fn peek_below(&mut self) -> Option<&'a T> {
+        let (mut i, mut j) = self.below;
+        while j == 0 && i > 0 {
+            i -= 1;
+            j = self.lists[i].len();
+        }
+        self.below = (i, j);
+        self.lists.get(i).and_then(|list| list[..j].last())
     }
 
-    pub fn pop_first(&mut self) -> Option<T> {
-        if self.is_empty() {
-            None
+    fn peek_above(&mut self) -> Option<&'a T> {
+        let (mut i, mut j) = self.above;
+        while i < self.lists.len() && j == self.lists[i].len() {
+            i += 1;
+            j = 0;
+        }
+        self.above = (i, j);
+        self.lists.get(i).map(|list| &list[j])
+    }
+}
+
+impl<'a, T: Ord + Clone + Sub<Output = T>> Iterator for NearestK<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let take_above = match (self.peek_below(), self.peek_above()) {
+            (Some(low), Some(high)) => {
+                high.clone() - self.val.clone() < self.val.clone() - low.clone()
+            }
+            (low, high) => low.is_none() && high.is_some(),
+        };
+        let next = if take_above {
+            let next = self.peek_above();
+            self.above.1 += 1;
+            next
         } else {
-            self.len -= 1;
-            let rv = Some(self.lists[0].remove(0));
-            self.contract(0);
-            rv
+            let next = self.peek_below();
+            self.below.1 = self.below.1.saturating_sub(1);
+            next
+        };
+        if next.is_some() {
+            self.remaining -= 1;
         }
+        next
     }
+}
 
-    pub fn pop_last(&mut self) -> Option<T> {
-        if let Some(rv) = self.lists.last_mut().and_then(|l| l.pop()) {
-            self.len -= 1;
-            let len = self.len;
-            self.contract(len);
-            Some(rv)
+impl<'a, T: Ord + Clone + Sub<Output = T>> FusedIterator for NearestK<'a, T> {}
+
+/// Gives mutable access to the elements of a `SortedList`, restoring its order when dropped.
+///
+/// Created by `SortedList::iter_mut_guarded`.
+pub struct IterMutGuard<'a, T: Ord + 'a> {
+    list: &'a mut SortedList<T>,
+}
+
+impl<'a, T: Ord> IterMutGuard<'a, T> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let mut outer = self.list.chunks.lists.iter_mut();
+        let inner = outer.next().map(|list| list.iter_mut()).unwrap_or_default();
+        IterMut { outer, inner }
+    }
+}
+
+impl<'a, 'b, T: Ord> IntoIterator for &'b mut IterMutGuard<'a, T> {
+    type Item = &'b mut T;
+    type IntoIter = IterMut<'b, T>;
+
+    fn into_iter(self) -> IterMut<'b, T> {
+        self.iter_mut()
+    }
+}
+
+impl<'a, T: Ord> Drop for IterMutGuard<'a, T> {
+    fn drop(&mut self) {
+        self.list.restore_order();
+    }
+}
+
+impl<T: Ord + InterpolatableKey> SortedList<T> {
+    /// Searches for `val` using interpolation search, both across the sublists and within them.
+    ///
+    /// Returns the same as `binary_search_by`, except that on a match the index is always that
+    /// of the first equal element. For uniformly distributed keys this needs fewer comparisons
+    /// than a binary search.
+    ///
+    /// This is the only search that interpolates; the other methods bisect regardless of `T`,
+    /// so callers that want interpolation must call this one explicitly.
+    pub fn interpolation_search(&self, val: &T) -> Result<usize, usize> {
+        if self.is_empty() {
+            return Err(0);
+        }
+
+        let target = val.key();
+        let i = interpolation_partition_point(
+            &self.chunks.lists,
+            target,
+            |list| list.last().unwrap().key(),
+            |list| list.last().unwrap() < val,
+        );
+        if i == self.chunks.lists.len() {
+            return Err(self.chunks.len);
+        }
+
+        let j = interpolation_partition_point(&self.chunks.lists[i], target, T::key, |x| x < val);
+        let index = self.chunks.flat_index(i, j);
+        if self.chunks.get_indices((i, j)) == Some(val) {
+            Ok(index)
         } else {
-            None
+            Err(index)
         }
     }
+}
 
-    pub fn len(&self) -> usize {
-        self.len
+impl<A: Ord, B: Ord> SortedList<(A, B)> {
+    /// Iterates over the pairs whose first component equals `a`, without needing sentinel
+    /// values for the second component.
+    pub fn range_prefix(&self, a: &A) -> Range<'_, (A, B)> {
+        let start = self.bisect_indices(|x| x.0 < *a);
+        let end = self.bisect_indices(|x| x.0 <= *a);
+        self.range_between(start, end)
     }
+}
 
-    pub fn is_empty(&self) -> bool {
-        self.len == 0
+impl<T: Ord> SortedRead<T> for SortedList<T> {
+    type Iter<'a>
+        = Iter<'a, T>
+    where
+        T: 'a;
+    type Range<'a>
+        = Range<'a, T>
+    where
+        T: 'a;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        self.get(i)
+    }
+
+    fn contains(&self, val: &T) -> bool {
+        self.contains(val)
+    }
+
+    fn rank(&self, val: &T) -> usize {
+        self.bisect_left(val)
+    }
+
+    fn range<R: RangeBounds<T>>(&self, range: R) -> Range<'_, T> {
+        self.range(range)
+    }
+
+    fn iter(&self) -> Iter<'_, T> {
+        self.iter()
+    }
+}
+
+impl<T: Ord> SortedWrite<T> for SortedList<T> {
+    fn add(&mut self, val: T) {
+        self.add(val)
+    }
+
+    fn pop_first(&mut self) -> Option<T> {
+        self.pop_first()
     }
 
-    pub fn iter(&self) -> Iter<T> {
-        let mut outer = self.lists.iter();
-        let inner = outer.next().unwrap().iter();
-        Iter { outer, inner }
+    fn pop_last(&mut self) -> Option<T> {
+        self.pop_last()
+    }
+
+    fn remove_with_index(&mut self, val: &T) -> Option<usize> {
+        self.remove_with_index(val)
     }
 }
 
@@ -172,29 +2022,16 @@ impl<T: Ord> Index<usize> for SortedList<T> {
     type Output = T;
 
     fn index(&self, i: usize) -> &T {
-        let mut i = i;
-        for list in &self.lists {
-            if list.len() > i {
-                return &list[i];
-            } else {
-                i -= list.len();
-            }
-        }
-        panic!("element greater than list size");
+        let (i, j) = self.chunks.expect_position_indices(i);
+        &self.chunks.lists[i][j]
     }
 }
 
 impl<T: Ord> IndexMut<usize> for SortedList<T> {
     fn index_mut(&mut self, i: usize) -> &mut T {
-        let mut i = i;
-        for list in &mut self.lists {
-            if list.len() > i {
-                return &mut list[i];
-            } else {
-                i -= list.len();
-            }
-        }
-        panic!("element greater than list size");
+        self.chunks.bump_version();
+        let (i, j) = self.chunks.expect_position_indices(i);
+        &mut self.chunks.lists[i][j]
     }
 }
 
@@ -204,7 +2041,7 @@ impl<T: Ord> IntoIterator for SortedList<T> {
 
     fn into_iter(self) -> IntoIter<T> {
         IntoIter {
-            outer: self.lists.into_iter(),
+            outer: self.chunks.lists.into_iter(),
             inner: Vec::new().into_iter(),
         }
     }
@@ -219,6 +2056,18 @@ impl<T: Ord> Default for SortedList<T> {
 /// Create a SortedList from an Iterator.
 ///
 /// The runtime of this function should be approximately `O(n * log(n))`.
+///
+/// Through the standard library's implementation for `Result`, an iterator of results can be
+/// collected into a `Result` holding a list, stopping at the first error:
+///
+/// ```
+/// use sorted_collections::SortedList;
+/// let parsed: Result<SortedList<u32>, _> = "3 1 2".split(' ').map(str::parse::<u32>).collect();
+/// assert!(parsed.unwrap().iter().eq([1, 2, 3].iter()));
+///
+/// let failed: Result<SortedList<u32>, _> = "3 x 2".split(' ').map(str::parse::<u32>).collect();
+/// assert!(failed.is_err());
+/// ```
 impl<T: Ord> FromIterator<T> for SortedList<T> {
     fn from_iter<F>(iter: F) -> Self
     where
@@ -231,3 +2080,35 @@ impl<T: Ord> FromIterator<T> for SortedList<T> {
         list
     }
 }
+
+/// Create a SortedList by cloning the referenced values, so that `values().collect()` and the
+/// like don't need a `cloned()`.
+///
+/// Since a list can be collected from either values or references, an iterator whose item type
+/// is still being inferred may need annotating, as with `str::parse::<u32>` above.
+///
+/// ```
+/// use sorted_collections::SortedList;
+/// use std::collections::HashMap;
+/// let ages: HashMap<&str, u32> = vec![("ann", 31), ("bob", 27)].into_iter().collect();
+/// let sorted: SortedList<u32> = ages.values().collect();
+/// assert!(sorted.iter().eq([27, 31].iter()));
+/// ```
+impl<'a, T: 'a + Ord + Clone> FromIterator<&'a T> for SortedList<T> {
+    fn from_iter<F>(iter: F) -> Self
+    where
+        F: IntoIterator<Item = &'a T>,
+    {
+        iter.into_iter().cloned().collect()
+    }
+}
+
+/// Adds each value in turn, as `add` does. For a large batch, sorting it first and using
+/// `merge_iter` is faster.
+impl<T: Ord> Extend<T> for SortedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.add(x);
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! Iterators over the union, intersection and differences of two sorted lists.
+//!
+//! Like `union_with` and the other in-place operations, these treat lists as multisets, so a
+//! value that occurs twice in one list and once in the other occurs twice in their union and
+//! once in their intersection. They walk both lists together in a single pass, and the
+//! intersection skips over runs of values missing from the other list by binary search.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::SortedList;
+//! let a: SortedList<u32> = vec![1, 2, 2, 3, 5].into_iter().collect();
+//! let b: SortedList<u32> = vec![2, 3, 4].into_iter().collect();
+//!
+//! assert!(a.union(&b).eq([1, 2, 2, 3, 4, 5].iter()));
+//! assert!(a.intersection(&b).eq([2, 3].iter()));
+//! assert!(a.difference(&b).eq([1, 2, 5].iter()));
+//! assert!(a.symmetric_difference(&b).eq([1, 2, 4, 5].iter()));
+//! ```
+
+use super::{Iter, SortedList};
+use std::cmp::Ordering;
+use std::iter::{FusedIterator, Peekable};
+
+impl<T: Ord> SortedList<T> {
+    /// Iterates over the values in either list, in order. A value occurs as many times as it
+    /// does in whichever list has more copies of it.
+    pub fn union<'a>(&'a self, other: &'a SortedList<T>) -> Union<'a, T> {
+        Union {
+            a: self.iter().peekable(),
+            b: other.iter().peekable(),
+        }
+    }
+
+    /// Iterates over the values in both lists, in order. A value occurs as many times as it
+    /// does in whichever list has fewer copies of it.
+    ///
+    /// Runs of values that aren't in the other list are skipped by binary search, first over
+    /// whole sublists and then within one, so this is fast when one list is much smaller.
+    pub fn intersection<'a>(&'a self, other: &'a SortedList<T>) -> Intersection<'a, T> {
+        Intersection {
+            a: &self.chunks.lists,
+            b: &other.chunks.lists,
+            a_position: (0, 0),
+            b_position: (0, 0),
+        }
+    }
+
+    /// Iterates over the values in this list that aren't in `other`, in order. Each copy of a
+    /// value in `other` cancels out one copy of it here.
+    pub fn difference<'a>(&'a self, other: &'a SortedList<T>) -> Difference<'a, T> {
+        Difference {
+            a: self.iter().peekable(),
+            b: other.iter().peekable(),
+        }
+    }
+
+    /// Iterates over the values in one list but not the other, in order. A value occurs as many
+    /// times as the difference between its counts in the two lists.
+    pub fn symmetric_difference<'a>(
+        &'a self,
+        other: &'a SortedList<T>,
+    ) -> SymmetricDifference<'a, T> {
+        SymmetricDifference {
+            a: self.iter().peekable(),
+            b: other.iter().peekable(),
+        }
+    }
+}
+
+/// Iterates over the union of two `SortedList`s.
+///
+/// Created by `SortedList::union`.
+pub struct Union<'a, T: 'a> {
+    a: Peekable<Iter<'a, T>>,
+    b: Peekable<Iter<'a, T>>,
+}
+
+impl<'a, T: Ord> Iterator for Union<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        match (self.a.peek(), self.b.peek()) {
+            (Some(x), Some(y)) => match x.cmp(y) {
+                Ordering::Less => self.a.next(),
+                Ordering::Greater => self.b.next(),
+                Ordering::Equal => {
+                    self.b.next();
+                    self.a.next()
+                }
+            },
+            (Some(_), None) => self.a.next(),
+            (None, _) => self.b.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_min, a_max) = self.a.size_hint();
+        let (b_min, b_max) = self.b.size_hint();
+        let max = match (a_max, b_max) {
+            (Some(a_max), Some(b_max)) => a_max.checked_add(b_max),
+            _ => None,
+        };
+        (a_min.max(b_min), max)
+    }
+}
+
+impl<'a, T: Ord> FusedIterator for Union<'a, T> {}
+
+/// Iterates over the intersection of two `SortedList`s.
+///
+/// Created by `SortedList::intersection`.
+#[derive(Debug)]
+pub struct Intersection<'a, T: 'a> {
+    a: &'a [Vec<T>],
+    b: &'a [Vec<T>],
+    // Positions are always of an element, or past the end of the last sublist.
+    a_position: (usize, usize),
+    b_position: (usize, usize),
+}
+
+impl<'a, T: Ord> Iterator for Intersection<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            let x = get(self.a, self.a_position)?;
+            let y = get(self.b, self.b_position)?;
+            match x.cmp(y) {
+                Ordering::Less => self.a_position = seek(self.a, self.a_position, y),
+                Ordering::Greater => self.b_position = seek(self.b, self.b_position, x),
+                Ordering::Equal => {
+                    self.a_position = step(self.a, self.a_position);
+                    self.b_position = step(self.b, self.b_position);
+                    return Some(x);
+                }
+            }
+        }
+    }
+}
+
+impl<'a, T: Ord> FusedIterator for Intersection<'a, T> {}
+
+fn get<T>(lists: &[Vec<T>], (i, j): (usize, usize)) -> Option<&T> {
+    lists.get(i).and_then(|list| list.get(j))
+}
+
+/// The position after an element's.
+fn step<T>(lists: &[Vec<T>], (i, j): (usize, usize)) -> (usize, usize) {
+    if j + 1 < lists[i].len() {
+        (i, j + 1)
+    } else {
+        (i + 1, 0)
+    }
+}
+
+/// The first position at or after an element's whose element isn't less than `target`.
+fn seek<T: Ord>(lists: &[Vec<T>], (i, j): (usize, usize), target: &T) -> (usize, usize) {
+    let list = &lists[i];
+    if list[list.len() - 1] >= *target {
+        return (i, j + list[j..].partition_point(|x| x < target));
+    }
+    let i = i + 1 + lists[i + 1..].partition_point(|list| list.last() < Some(target));
+    match lists.get(i) {
+        Some(list) => (i, list.partition_point(|x| x < target)),
+        None => (i, 0),
+    }
+}
+
+/// Iterates over the difference of two `SortedList`s.
+///
+/// Created by `SortedList::difference`.
+pub struct Difference<'a, T: 'a> {
+    a: Peekable<Iter<'a, T>>,
+    b: Peekable<Iter<'a, T>>,
+}
+
+impl<'a, T: Ord> Iterator for Difference<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        for x in self.a.by_ref() {
+            while self.b.next_if(|y| *y < x).is_some() {}
+            if self.b.next_if(|y| *y == x).is_none() {
+                return Some(x);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.a.size_hint().1)
+    }
+}
+
+impl<'a, T: Ord> FusedIterator for Difference<'a, T> {}
+
+/// Iterates over the symmetric difference of two `SortedList`s.
+///
+/// Created by `SortedList::symmetric_difference`.
+pub struct SymmetricDifference<'a, T: 'a> {
+    a: Peekable<Iter<'a, T>>,
+    b: Peekable<Iter<'a, T>>,
+}
+
+impl<'a, T: Ord> Iterator for SymmetricDifference<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            match (self.a.peek(), self.b.peek()) {
+                (Some(x), Some(y)) => match x.cmp(y) {
+                    Ordering::Less => return self.a.next(),
+                    Ordering::Greater => return self.b.next(),
+                    Ordering::Equal => {
+                        self.a.next();
+                        self.b.next();
+                    }
+                },
+                (Some(_), None) => return self.a.next(),
+                (None, _) => return self.b.next(),
+            }
+        }
+    }
+}
+
+impl<'a, T: Ord> FusedIterator for SymmetricDifference<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intersection_skips_sublists() {
+        let big: SortedList<u32> = (0..100_000).collect();
+        let small: SortedList<u32> = vec![5, 50_000, 99_999, 200_000].into_iter().collect();
+        assert!(big.intersection(&small).eq([5, 50_000, 99_999].iter()));
+        assert!(small.intersection(&big).eq([5, 50_000, 99_999].iter()));
+        assert_eq!(0, SortedList::new().intersection(&big).count());
+        assert_eq!(0, big.intersection(&SortedList::new()).count());
+    }
+}
@@ -1,10 +1,52 @@
 use super::SortedList;
+use chunked::ChunkedStorage;
+use LoadFactor;
+
+/// Builds a list with sublists small enough that a few dozen values span several of them.
+fn small_chunk_list<T: Ord + Clone>(vals: &[T]) -> SortedList<T> {
+    let mut list = SortedList::with_growth_policy(LoadFactor(4));
+    for x in vals {
+        list.add(x.clone());
+    }
+    list
+}
 
 #[test]
 fn it_builds() {
     let default = SortedList::<u8>::default();
-    assert!(default.lists.len() == 1);
-    assert!(default.lists[0].len() == 0);
+    assert!(default.chunks.lists.is_empty());
+    assert_eq!(0, default.chunks.lists.capacity());
+}
+
+static EMPTY: SortedList<u32> = SortedList::new();
+
+#[test]
+fn reads_before_first_add() {
+    use SortedRead;
+
+    assert!(EMPTY.is_empty());
+    assert_eq!(None, EMPTY.first());
+    assert!(!EMPTY.contains(&1));
+    assert_eq!(None, EMPTY.iter().next());
+    assert_eq!(0, EMPTY.range(1..5).count());
+    assert_eq!(0, EMPTY.rank(&3));
+    assert_eq!(None, SortedRead::get(&EMPTY, 0));
+    assert_eq!(Err(0), EMPTY.binary_search_by(|x| x.cmp(&3)));
+    assert_eq!(None, EMPTY.closest(&3));
+    assert_eq!(None, EMPTY.iter_distinct().next());
+    assert_eq!(vec![0, 0], EMPTY.histogram(&[4]));
+    assert_eq!(0, EMPTY.range_by_key(&1, |x| *x).count());
+
+    let mut list = SortedList::new();
+    assert_eq!(None, list.pop_first());
+    assert_eq!(None, list.pop_last());
+    assert_eq!(None, list.last());
+    assert_eq!(0, list.iter_mut_guarded().iter_mut().count());
+    assert_eq!(&4, list.get_or_insert_with(&4, || 4));
+
+    let mut list = SortedList::new();
+    list.merge_iter(vec![1, 2]);
+    assert_eq!(2, list.len());
 }
 
 #[test]
@@ -38,8 +80,8 @@ fn basic_test() {
 
     assert!(!list.contains(&3));
 
-    assert_eq!(1, list.lists.len());
-    assert_eq!(0, list.lists[0].len());
+    assert_eq!(1, list.chunks.lists.len());
+    assert_eq!(0, list.chunks.lists[0].len());
 
     assert_eq!(0, list.len());
 
@@ -97,19 +139,20 @@ fn ones() {
 #[should_panic]
 fn out_of_bounds_panics() {
     let list: SortedList<i32> = SortedList::default();
-    list[0];
+    let _ = list[0];
 }
 
 #[test]
 fn test_actual_contract() {
     let mut list = SortedList::<i32> {
-        lists: vec![vec![-6, -5, -3], vec![1, 2, 3, 4, 5], vec![99, 100]],
-        load_factor: 2,
-        len: 10,
+        chunks: ChunkedStorage::from_lists(
+            vec![vec![-6, -5, -3], vec![1, 2, 3, 4, 5], vec![99, 100]],
+            Box::new(LoadFactor(2)),
+        ),
     };
-    list.unchecked_contract(1);
+    list.chunks.unchecked_contract(1);
     assert_eq!(
-        list.lists,
+        list.chunks.lists,
         vec![vec![-6, -5, -3], vec![1, 2, 3, 4, 5, 99, 100]]
     );
 }
@@ -117,7 +160,7 @@ fn test_actual_contract() {
 fn prop_from_iter_sorted<T: Ord + Clone>(list: Vec<T>) -> bool {
     let mut list = list.clone(); // can't get mutable values from quickcheck.
     list.sort();
-    let from_iter: SortedList<T> = list.iter().map(|x| x.clone()).collect();
+    let from_iter: SortedList<T> = list.iter().cloned().collect();
     let from_collection = {
         let mut collection = SortedList::default();
         for x in list.iter() {
@@ -158,3 +201,1241 @@ quickcheck! {
         prop_from_iter_sorted(list)
     }
 }
+
+#[test]
+fn mode_and_most_common() {
+    let empty: SortedList<i32> = SortedList::default();
+    assert_eq!(None, empty.mode());
+    assert!(empty.most_common(3).is_empty());
+
+    let list: SortedList<i32> = vec![5, 1, 3, 3, 5, 2, 5, 1, 3].into_iter().collect();
+    assert_eq!(Some((&3, 3)), list.mode());
+    assert_eq!(vec![(&3, 3), (&5, 3)], list.most_common(2));
+    assert_eq!(
+        vec![(&3, 3), (&5, 3), (&1, 2), (&2, 1)],
+        list.most_common(10)
+    );
+}
+
+#[test]
+fn mode_spans_sublists() {
+    let mut list = SortedList::default();
+    for i in 0..3000 {
+        list.add(i % 3 == 0);
+    }
+    assert!(list.chunks.lists.len() > 1);
+    assert_eq!(Some((&false, 2000)), list.mode());
+    assert_eq!(vec![(&false, 2000), (&true, 1000)], list.most_common(2));
+}
+
+#[test]
+fn as_slices() {
+    let empty: SortedList<i32> = SortedList::default();
+    assert_eq!(0, empty.as_slices().count());
+
+    let list: SortedList<u32> = (0..5000).rev().collect();
+    assert!(list.as_slices().count() > 1);
+    assert!(list.as_slices().all(|s| s.windows(2).all(|w| w[0] <= w[1])));
+    assert_eq!(
+        (0..5000).collect::<Vec<_>>(),
+        list.as_slices().collect::<Vec<_>>().concat()
+    );
+}
+
+#[test]
+fn binary_search_by_key() {
+    let list: SortedList<(u32, char)> = (0..5000).map(|i| (i * 2, 'x')).collect();
+    assert!(list.chunks.lists.len() > 1);
+
+    assert_eq!(Ok(0), list.binary_search_by_key(&0, |&(k, _)| k));
+    assert_eq!(Ok(1500), list.binary_search_by_key(&3000, |&(k, _)| k));
+    assert_eq!(Err(1501), list.binary_search_by_key(&3001, |&(k, _)| k));
+    assert_eq!(Err(5000), list.binary_search_by_key(&10000, |&(k, _)| k));
+    assert_eq!(Ok(4999), list.binary_search_by(|&(k, _)| k.cmp(&9998)));
+
+    let empty: SortedList<u32> = SortedList::default();
+    assert_eq!(Err(0), empty.binary_search_by(|x| x.cmp(&1)));
+}
+
+fn counts(vals: &[u8]) -> std::collections::BTreeMap<u8, usize> {
+    let mut counts = std::collections::BTreeMap::new();
+    for &x in vals {
+        *counts.entry(x).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn from_counts<F: Fn(usize, usize) -> usize>(a: &[u8], b: &[u8], f: F) -> Vec<u8> {
+    let (a, b) = (counts(a), counts(b));
+    let mut result = vec![];
+    for x in a
+        .keys()
+        .chain(b.keys())
+        .collect::<std::collections::BTreeSet<_>>()
+    {
+        let n = f(*a.get(x).unwrap_or(&0), *b.get(x).unwrap_or(&0));
+        result.extend(std::iter::repeat_n(*x, n));
+    }
+    result
+}
+
+#[test]
+fn set_mutations_across_sublists() {
+    let evens: SortedList<u32> = (0..6000).filter(|x| x % 2 == 0).collect();
+    let threes: SortedList<u32> = (0..6000).filter(|x| x % 3 == 0).collect();
+
+    let mut union: SortedList<u32> = evens.iter().cloned().collect();
+    union.union_with(threes.iter().cloned().collect());
+    assert!(union
+        .iter()
+        .cloned()
+        .eq((0..6000).filter(|x| x % 2 == 0 || x % 3 == 0)));
+    assert_eq!(4000, union.len());
+
+    let mut intersection: SortedList<u32> = evens.iter().cloned().collect();
+    intersection.intersect_with(&threes);
+    assert!(intersection
+        .iter()
+        .cloned()
+        .eq((0..6000).filter(|x| x % 6 == 0)));
+    assert_eq!(1000, intersection.len());
+
+    let mut difference: SortedList<u32> = evens.iter().cloned().collect();
+    difference.difference_with(&threes);
+    assert!(difference
+        .iter()
+        .cloned()
+        .eq((0..6000).filter(|x| x % 2 == 0 && x % 3 != 0)));
+    assert_eq!(2000, difference.len());
+}
+
+quickcheck! {
+    fn prop_set_op_iterators(a: Vec<u8>, b: Vec<u8>) -> bool {
+        let (x, y) = (small_chunk_list(&a), small_chunk_list(&b));
+        x.union(&y).cloned().eq(from_counts(&a, &b, std::cmp::max))
+            && x.intersection(&y).cloned().eq(from_counts(&a, &b, std::cmp::min))
+            && x.difference(&y).cloned().eq(from_counts(&a, &b, |m, n| m.saturating_sub(n)))
+            && x.symmetric_difference(&y).cloned().eq(from_counts(&a, &b, |m, n| m.abs_diff(n)))
+    }
+
+    fn prop_merge(a: Vec<u8>, b: Vec<(u8, bool)>) -> bool {
+        let left = small_chunk_list(&a.iter().map(|&x| (x, false)).collect::<Vec<_>>());
+        let right: SortedList<(u8, bool)> = b.iter().map(|&(x, _)| (x, true)).collect();
+        let mut expected: Vec<(u8, bool)> = left.iter().chain(right.iter()).cloned().collect();
+        expected.sort();
+        let merged = left.merge(right);
+        merged.iter().eq(expected.iter()) && merged.chunks.check()
+    }
+
+    fn prop_union_with(a: Vec<u8>, b: Vec<u8>) -> bool {
+        let mut list: SortedList<u8> = a.iter().cloned().collect();
+        list.union_with(b.iter().cloned().collect());
+        list.iter().cloned().eq(from_counts(&a, &b, std::cmp::max)) && list.len() == list.iter().count()
+    }
+
+    fn prop_intersect_with(a: Vec<u8>, b: Vec<u8>) -> bool {
+        let mut list: SortedList<u8> = a.iter().cloned().collect();
+        list.intersect_with(&b.iter().cloned().collect());
+        list.iter().cloned().eq(from_counts(&a, &b, std::cmp::min)) && list.len() == list.iter().count()
+    }
+
+    fn prop_difference_with(a: Vec<u8>, b: Vec<u8>) -> bool {
+        let mut list: SortedList<u8> = a.iter().cloned().collect();
+        list.difference_with(&b.iter().cloned().collect());
+        list.iter().cloned().eq(from_counts(&a, &b, |x, y| x.saturating_sub(y)))
+            && list.len() == list.iter().count()
+    }
+
+    fn prop_set_lens(a: Vec<u8>, b: Vec<u8>) -> bool {
+        let (x, y): (SortedList<u8>, SortedList<u8>) =
+            (a.iter().cloned().collect(), b.iter().cloned().collect());
+        let intersection = from_counts(&a, &b, std::cmp::min).len();
+        let union = from_counts(&a, &b, std::cmp::max).len();
+        let jaccard = if union == 0 { 1.0 } else { intersection as f64 / union as f64 };
+        x.intersection_len(&y) == intersection
+            && y.intersection_len(&x) == intersection
+            && x.union_len(&y) == union
+            && x.jaccard(&y) == jaccard
+    }
+}
+
+#[test]
+fn closest() {
+    let empty: SortedList<u32> = SortedList::default();
+    assert_eq!(None, empty.closest(&4));
+
+    let list: SortedList<u32> = (1..3000).map(|x| x * 10).collect();
+    assert_eq!(Some(&10), list.closest(&0));
+    assert_eq!(Some(&10), list.closest(&14));
+    assert_eq!(Some(&10), list.closest(&15));
+    assert_eq!(Some(&20), list.closest(&16));
+    assert_eq!(Some(&12340), list.closest(&12340));
+    assert_eq!(Some(&29990), list.closest(&u32::MAX));
+}
+
+quickcheck! {
+    fn prop_interpolation_search(vals: Vec<i16>, target: i16) -> bool {
+        let list: SortedList<i16> = vals.into_iter().collect();
+        let expected = match list.iter().position(|x| *x >= target) {
+            Some(i) if list[i] == target => Ok(i),
+            Some(i) => Err(i),
+            None => Err(list.len()),
+        };
+        list.interpolation_search(&target) == expected
+    }
+}
+
+#[test]
+fn interpolation_search_across_sublists() {
+    let list: SortedList<u64> = (0..10000).map(|x| x * 3).collect();
+    for &(val, expected) in &[
+        (0, Ok(0)),
+        (1, Err(1)),
+        (2999 * 3, Ok(2999)),
+        (30000, Err(10000)),
+    ] {
+        assert_eq!(expected, list.interpolation_search(&val));
+    }
+}
+
+#[test]
+fn push_pop_top_k() {
+    // Keep the 100 largest values seen.
+    let mut top: SortedList<u32> = SortedList::default();
+    for i in 0..5000u32 {
+        let x = (i * 7919) % 5003;
+        if top.len() < 100 {
+            top.add(x);
+        } else {
+            top.push_pop_first(x);
+        }
+    }
+    assert!(top.iter().cloned().eq(4903..5003));
+}
+
+#[test]
+fn replace_first_and_last() {
+    let mut list: SortedList<u32> = (0..10).collect();
+    assert_eq!(Some(0), list.replace_first(100));
+    assert_eq!(Some(100), list.replace_last(5));
+    assert!(list.iter().cloned().eq(vec![1, 2, 3, 4, 5, 5, 6, 7, 8, 9]));
+
+    let mut empty = SortedList::default();
+    assert_eq!(None, empty.replace_first(3));
+    assert_eq!(1, empty.len());
+}
+
+quickcheck! {
+    fn prop_push_pop(vals: Vec<i16>, x: i16) -> bool {
+        let mut sorted = vals.clone();
+        sorted.push(x);
+        sorted.sort();
+
+        let mut first: SortedList<i16> = vals.iter().cloned().collect();
+        let mut last: SortedList<i16> = vals.iter().cloned().collect();
+        let popped_first = first.push_pop_first(x);
+        let popped_last = last.push_pop_last(x);
+
+        popped_first == sorted[0]
+            && first.iter().eq(sorted[1..].iter())
+            && popped_last == sorted[sorted.len() - 1]
+            && last.iter().eq(sorted[..sorted.len() - 1].iter())
+            && first.len() == vals.len()
+            && last.len() == vals.len()
+    }
+}
+
+#[test]
+fn positions_from_add_and_remove() {
+    // A parallel vector of labels kept in step with the list's positions.
+    let mut list = SortedList::default();
+    let mut labels = Vec::new();
+    for i in 0..3000u32 {
+        let x = (i * 7919) % 3001;
+        let index = list.add_with_index(x);
+        labels.insert(index, x.to_string());
+    }
+    assert!(list.chunks.lists.len() > 1);
+    assert!(list
+        .iter()
+        .map(|x| x.to_string())
+        .eq(labels.iter().cloned()));
+
+    for x in (0..3001).filter(|x| x % 3 != 2) {
+        if let Some(index) = list.remove_with_index(&x) {
+            assert_eq!(x.to_string(), labels.remove(index));
+        }
+    }
+    assert_eq!(None, list.remove_with_index(&0));
+    assert_eq!(labels.len(), list.len());
+    assert!(list
+        .iter()
+        .map(|x| x.to_string())
+        .eq(labels.iter().cloned()));
+}
+
+#[test]
+fn iter_mut_guarded_keeps_order() {
+    let mut list: SortedList<i64> = (0..5000).collect();
+    for x in &mut list.iter_mut_guarded() {
+        *x += 1;
+    }
+    assert!(list.iter().cloned().eq(1..5001));
+    let lists = list.chunks.lists.len();
+
+    for x in &mut list.iter_mut_guarded() {
+        *x = (*x - 2500).abs();
+    }
+    let mut expected: Vec<i64> = (1..5001).map(|x: i64| (x - 2500).abs()).collect();
+    expected.sort();
+    assert!(list.iter().eq(expected.iter()));
+    assert_eq!(lists, list.chunks.lists.len());
+    assert_eq!(5000, list.len());
+}
+
+#[test]
+fn fill_sorted_sizes() {
+    let mut list = SortedList::<usize>::with_growth_policy(LoadFactor(10));
+    for n in 0..45 {
+        list.fill_sorted((0..n).collect());
+        assert!(list.iter().cloned().eq(0..n));
+        assert_eq!(n, list.len());
+        assert!(list
+            .chunks
+            .lists
+            .iter()
+            .all(|l| l.len() <= 10 && (n < 5 || l.len() >= 5)));
+    }
+}
+
+#[test]
+fn map_monotonic() {
+    let list: SortedList<u32> = (0..5000).collect();
+    let mapped = list.map_monotonic(|x| i64::from(x) * 3 - 7);
+    assert_eq!(5000, mapped.len());
+    assert!(mapped.iter().cloned().eq((0..5000).map(|x| x * 3 - 7)));
+}
+
+#[test]
+#[should_panic]
+#[cfg(debug_assertions)]
+fn map_monotonic_checks_order() {
+    let list: SortedList<i32> = (0..10).collect();
+    list.map_monotonic(|x| -x);
+}
+
+#[test]
+fn merge_iter_batches() {
+    let mut list: SortedList<u32> = (0..10000).filter(|x| x % 3 == 0).collect();
+    list.merge_iter((0..10000).filter(|x| x % 3 == 1));
+    list.merge_iter(std::iter::empty());
+    list.merge_iter((0..10000).filter(|x| x % 3 == 2));
+    list.merge_iter(vec![20000, 20000]);
+
+    assert_eq!(10002, list.len());
+    assert!(list
+        .iter()
+        .cloned()
+        .eq((0..10000).chain(vec![20000, 20000])));
+    assert!(list
+        .chunks
+        .lists
+        .iter()
+        .all(|l| !l.is_empty() && !list.chunks.should_split(l.len())));
+}
+
+#[test]
+#[should_panic]
+#[cfg(debug_assertions)]
+fn merge_iter_checks_order() {
+    let mut list: SortedList<u32> = (0..10).collect();
+    list.merge_iter(vec![5, 3]);
+}
+
+#[test]
+fn merge_adopts_disjoint_sublists() {
+    let mut low = SortedList::with_growth_policy(LoadFactor(4));
+    let mut high = SortedList::with_growth_policy(LoadFactor(4));
+    for x in 0..100 {
+        low.add(x);
+        high.add(x + 100);
+    }
+    let n_lists = low.chunks.lists.len() + high.chunks.lists.len();
+    let merged = high.merge(low);
+    assert_eq!(n_lists, merged.chunks.lists.len());
+    assert!(merged.iter().eq((0..200).collect::<Vec<_>>().iter()));
+}
+
+#[test]
+fn union_all() {
+    let shards: Vec<SortedList<u32>> = (0..4)
+        .map(|shard| (0..3000).filter(|x| x % 4 == shard || x % 7 == 0).collect())
+        .collect();
+    let mut expected: Vec<u32> = shards.iter().flat_map(|s| s.iter().cloned()).collect();
+    expected.sort();
+
+    let combined = SortedList::union_all(shards);
+    assert_eq!(expected.len(), combined.len());
+    assert!(combined.iter().eq(expected.iter()));
+
+    assert!(SortedList::<u8>::union_all(vec![]).is_empty());
+    assert!(SortedList::<u8>::union_all(vec![SortedList::new()]).is_empty());
+}
+
+#[test]
+fn histogram() {
+    use std::ops::Bound::*;
+
+    let list: SortedList<u32> = (0..5000).collect();
+    assert_eq!(
+        vec![100, 900, 0, 4000, 0],
+        list.histogram(&[100, 1000, 1000, 8000])
+    );
+    assert_eq!(vec![5000], list.histogram(&[]));
+
+    let buckets: Vec<_> = list.histogram_iter(&[10, 20]).collect();
+    assert_eq!(
+        vec![
+            ((Unbounded, Excluded(&10)), 10),
+            ((Included(&10), Excluded(&20)), 10),
+            ((Included(&20), Unbounded), 4980),
+        ],
+        buckets
+    );
+}
+
+quickcheck! {
+    fn prop_split_on_insert(vals: Vec<u8>) -> bool {
+        let mut list = SortedList::<u8>::with_growth_policy(LoadFactor(4));
+        let mut sorted = Vec::new();
+        for x in vals {
+            let index = list.add_with_index(x);
+            if sorted[..index].iter().any(|y| *y > x) || sorted[index..].iter().any(|y| *y < x) {
+                return false;
+            }
+            sorted.insert(index, x);
+        }
+        list.iter().eq(sorted.iter())
+            && list.chunks.lists.iter().all(|l| l.len() < 8)
+            && (list.chunks.lists.len() == 1 || list.chunks.lists.iter().all(|l| l.len() >= 2))
+    }
+}
+
+#[test]
+fn dedup_by_across_sublists() {
+    let mut list = SortedList::<(u8, u32)> {
+        chunks: ChunkedStorage::from_lists(
+            vec![
+                vec![(0, 1), (0, 2), (1, 3)],
+                vec![(1, 4), (1, 5)],
+                vec![(1, 6), (2, 7)],
+            ],
+            Box::new(LoadFactor(4)),
+        ),
+    };
+    list.dedup_by_key(|&(day, _)| day);
+    assert_eq!(
+        vec![(0, 1), (1, 3), (2, 7)],
+        list.iter().cloned().collect::<Vec<_>>()
+    );
+    assert_eq!(3, list.len());
+}
+
+quickcheck! {
+    fn prop_dedup_by(vals: Vec<u8>) -> bool {
+        let mut list = small_chunk_list(&vals);
+        let mut sorted = vals;
+        sorted.sort();
+        sorted.dedup_by(|a, b| *a / 10 == *b / 10);
+        list.dedup_by(|a, b| *a / 10 == *b / 10);
+        list.iter().eq(sorted.iter()) && list.len() == sorted.len()
+    }
+
+    fn prop_dedup(vals: Vec<u8>) -> bool {
+        let mut sorted: Vec<u8> = vals.iter().map(|x| x % 16).collect();
+        let mut list = small_chunk_list(&sorted);
+        sorted.sort();
+        sorted.dedup();
+        list.dedup();
+        list.iter().eq(sorted.iter()) && list.chunks.check()
+    }
+}
+
+#[test]
+fn get_or_insert_with() {
+    let mut list = SortedList::<String>::with_growth_policy(LoadFactor(4));
+    let words = ["b", "a", "c", "b", "d", "a", "e", "f", "g", "h", "e"];
+    let mut calls = 0;
+    for word in &words {
+        let interned = list.get_or_insert_with(&word.to_string(), || {
+            calls += 1;
+            word.to_string()
+        });
+        assert_eq!(word, interned);
+    }
+    assert_eq!(8, calls);
+    assert_eq!(8, list.len());
+    assert!(list
+        .iter()
+        .map(String::as_str)
+        .eq(["a", "b", "c", "d", "e", "f", "g", "h"]));
+    assert!(list.chunks.lists.len() > 1);
+}
+
+/// Ordered by `key` alone, so that elements can be equal with different payloads.
+#[derive(Clone, Debug)]
+pub(super) struct Keyed {
+    pub(super) key: u32,
+    pub(super) payload: &'static str,
+}
+
+impl PartialEq for Keyed {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Keyed {}
+
+impl PartialOrd for Keyed {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Keyed {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+#[test]
+fn replace() {
+    let mut list = SortedList::with_growth_policy(LoadFactor(4));
+    for key in 0..20 {
+        assert!(list
+            .replace(Keyed {
+                key,
+                payload: "old"
+            })
+            .is_none());
+    }
+    let version = list.version();
+    let old = list
+        .replace(Keyed {
+            key: 13,
+            payload: "new",
+        })
+        .unwrap();
+    assert_eq!("old", old.payload);
+    assert!(list.version() > version);
+    assert_eq!(20, list.len());
+    assert!(list.chunks.check());
+    assert!(list
+        .iter()
+        .all(|x| x.payload == if x.key == 13 { "new" } else { "old" }));
+}
+
+quickcheck! {
+    fn prop_range_prefix(vals: Vec<(u8, i16)>, a: u8) -> bool {
+        let list = small_chunk_list(&vals);
+        let mut expected: Vec<_> = vals.into_iter().filter(|x| x.0 == a).collect();
+        expected.sort();
+        list.range_prefix(&a).len() == expected.len()
+            && list.range_prefix(&a).eq(expected.iter())
+            && list.range_by_key(&(a / 2), |x| x.0 / 2).all(|x| x.0 / 2 == a / 2)
+    }
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn choose() {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let mut rng = StdRng::seed_from_u64(7);
+    assert_eq!(None, SortedList::<u32>::new().choose(&mut rng));
+
+    let list: SortedList<u32> = (0..3000).collect();
+    let mut seen = [false; 3];
+    for _ in 0..100 {
+        let x = *list.choose(&mut rng).unwrap();
+        assert!(x < 3000);
+        seen[x as usize / 1000] = true;
+    }
+    assert_eq!([true; 3], seen);
+}
+
+quickcheck! {
+    fn prop_iter_distinct(vals: Vec<u8>) -> bool {
+        let mut expected: Vec<u8> = vals.into_iter().map(|x| x / 8).collect();
+        let list = small_chunk_list(&expected);
+        expected.sort();
+        expected.dedup();
+        list.iter_distinct().eq(expected.iter())
+    }
+}
+
+#[test]
+fn from_chunks() {
+    use super::UnsortedChunks;
+
+    let runs: Vec<Vec<u32>> = (0..4).map(|t| (t * 700..(t + 1) * 700).collect()).collect();
+    let list = SortedList::from_chunks(runs).unwrap();
+    assert_eq!(2800, list.len());
+    assert!(list.chunks.check());
+    assert!(list.iter().cloned().eq(0..2800));
+
+    let chunks = list.into_chunks();
+    assert!(chunks.iter().all(|chunk| !chunk.is_empty()));
+    assert!(chunks.into_iter().flatten().eq(0..2800));
+
+    let unsorted = vec![vec![1, 5], vec![], vec![4, 6]];
+    match SortedList::from_chunks(unsorted.clone()) {
+        Err(UnsortedChunks(chunks)) => assert_eq!(unsorted, chunks),
+        Ok(_) => panic!("unsorted chunks were accepted"),
+    }
+
+    let empty = SortedList::<u32>::from_chunks(vec![vec![], vec![]]).unwrap();
+    assert!(empty.is_empty());
+    assert!(empty.into_chunks().is_empty());
+    assert!(SortedList::<u32>::new().into_chunks().is_empty());
+}
+
+#[test]
+fn from_sorted() {
+    let list = SortedList::from_sorted_vec((0..10_000).collect());
+    assert_eq!(10_000, list.len());
+    assert!(list.chunks.check());
+    assert!(list.iter().cloned().eq(0..10_000));
+
+    let list = SortedList::from_sorted_iter((0..100).map(|x| x / 3));
+    assert_eq!(34, list.iter_distinct().count());
+    assert!(SortedList::<u32>::from_sorted_iter(None).is_empty());
+}
+
+quickcheck! {
+    fn prop_into_vec(vals: Vec<u8>) -> bool {
+        let list = small_chunk_list(&vals);
+        let mut sorted = vals;
+        sorted.sort();
+        list.into_vec() == sorted
+    }
+}
+
+#[test]
+#[should_panic(expected = "unsorted")]
+#[cfg(debug_assertions)]
+fn from_sorted_vec_checks_order() {
+    SortedList::from_sorted_vec(vec![1, 3, 2]);
+}
+
+quickcheck! {
+    fn prop_gaps(vals: Vec<i16>) -> bool {
+        let mut sorted: Vec<i32> = vals.into_iter().map(i32::from).collect();
+        let list = small_chunk_list(&sorted);
+        sorted.sort();
+        let gaps: Vec<i32> = sorted.windows(2).map(|w| w[1] - w[0]).collect();
+        let widest = gaps.iter().max();
+        let first = sorted.windows(2).find(|w| Some(&(w[1] - w[0])) == widest);
+
+        list.gaps().eq(gaps.iter().cloned())
+            && list.gaps_by(|a, b| (b - a) * 2).eq(gaps.iter().map(|x| x * 2))
+            && list.max_gap() == first.map(|w| (&w[0], &w[1]))
+            && list.max_gap_by(|a, b| f64::from(b - a)) == list.max_gap()
+    }
+}
+
+/// Claims to be less than everything but itself, which isn't antisymmetric.
+#[cfg(feature = "check-ord")]
+#[derive(Debug, PartialEq, Eq)]
+struct AlwaysLess(u8);
+
+#[cfg(feature = "check-ord")]
+impl PartialOrd for AlwaysLess {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "check-ord")]
+impl Ord for AlwaysLess {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        if std::ptr::eq(self, other) {
+            std::cmp::Ordering::Equal
+        } else {
+            std::cmp::Ordering::Less
+        }
+    }
+}
+
+#[cfg(feature = "check-ord")]
+#[test]
+#[should_panic(expected = "inconsistent Ord")]
+fn check_ord() {
+    let mut list = SortedList::new();
+    list.add(AlwaysLess(1));
+    list.add(AlwaysLess(2));
+}
+
+quickcheck! {
+    fn prop_from_slice(first: Vec<u8>, second: Vec<u8>) -> bool {
+        let mut list = SortedList::from_slice(&first);
+        let sorted_first = list.chunks.check() && list.iter().is_sorted();
+        list.extend_from_slice(&second);
+
+        let mut expected: Vec<u8> = first.iter().chain(second.iter()).cloned().collect();
+        expected.sort();
+        let mut sorted = first.clone();
+        sorted.sort();
+        sorted_first
+            && list.iter().eq(expected.iter())
+            && list.chunks.check()
+            && SortedList::from_slice(&sorted).iter().eq(sorted.iter())
+    }
+}
+
+#[test]
+fn take_all_and_replace_contents() {
+    let mut list = SortedList::with_growth_policy(LoadFactor(4));
+    list.merge_iter(0..20);
+    let taken = list.take_all();
+    assert!(list.is_empty());
+    assert!(taken.iter().cloned().eq(0..20));
+    assert_eq!(4, taken.chunks.chunk_size());
+
+    list.merge_iter(0..20);
+    assert!(list.chunks.check());
+    assert!(list.chunks.lists.len() > 1);
+
+    let other: SortedList<i32> = (100..3000).collect();
+    let old = list.replace_contents(other);
+    assert!(old.iter().cloned().eq(0..20));
+    assert!(list.iter().cloned().eq(100..3000));
+    assert!(list.chunks.check());
+    assert_eq!(4, list.chunks.chunk_size());
+    assert_eq!(4, old.chunks.chunk_size());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_retain_and_for_each_chunk() {
+    let mut list: SortedList<i32> = (0..10000).collect();
+    list.par_retain(|x| x % 3 == 0);
+    assert!(list.iter().cloned().eq((0..10000).filter(|x| x % 3 == 0)));
+    assert!(list.chunks.check());
+
+    list.par_for_each_chunk(|chunk| {
+        for x in chunk {
+            *x = -*x;
+        }
+    });
+    assert!(list
+        .iter()
+        .cloned()
+        .eq((0..10000).filter(|x| x % 3 == 0).map(|x| -x).rev()));
+    assert!(list.chunks.check());
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn index_from_end_out_of_range() {
+    let list: SortedList<u32> = (0..10).collect();
+    list.index_from_end(10);
+}
+
+quickcheck! {
+    fn prop_has_duplicates(vals: Vec<u8>) -> bool {
+        let list = small_chunk_list(&vals);
+        let mut distinct = vals.clone();
+        distinct.sort();
+        distinct.dedup();
+        let duplicated = distinct.len() < vals.len();
+        list.has_duplicates() == duplicated && list.is_strictly_increasing() != duplicated
+    }
+}
+
+#[test]
+fn split_into() {
+    let list: SortedList<u32> = (0..10000).collect();
+    let parts = list.split_into(3);
+    assert_eq!(3, parts.len());
+    assert!(parts.iter().all(|part| part.len().abs_diff(3333) <= 1000));
+    assert!(parts
+        .windows(2)
+        .all(|pair| pair[0].iter().last() < pair[1].first()));
+    assert!(parts
+        .iter()
+        .flat_map(SortedList::iter)
+        .cloned()
+        .eq(0..10000));
+
+    let parts = SortedList::<u32>::new().split_into(2);
+    assert!(parts.iter().all(SortedList::is_empty));
+}
+
+#[test]
+fn rebalance() {
+    let mut list = SortedList::with_growth_policy(LoadFactor(4));
+    for x in 0..1000 {
+        list.add(x);
+    }
+    for x in (0..1000).filter(|x| x % 3 != 0) {
+        list.remove_with_index(&x);
+    }
+    assert!(list.fragmentation() > 0.2);
+
+    list.rebalance();
+    assert_eq!(0.0, list.fragmentation());
+    assert!(list.chunks.check());
+    assert!(list.iter().cloned().eq((0..1000).step_by(3)));
+    assert!(list.chunks.lists.iter().all(|l| l.capacity() == l.len()));
+
+    let mut empty = SortedList::<u32>::new();
+    empty.rebalance();
+    assert_eq!(0.0, empty.fragmentation());
+    empty.add(1);
+    assert_eq!(1, empty.len());
+}
+
+#[test]
+fn remove_indices() {
+    let mut list: SortedList<u32> = (0..5000).collect();
+    let removed = list.remove_indices((0..5000).rev().step_by(2));
+    assert!(removed.into_iter().eq((1..5000).step_by(2)));
+    assert!(list.iter().cloned().eq((0..5000).step_by(2)));
+    assert!(list.chunks.check());
+    assert!(list.remove_indices(None).is_empty());
+}
+
+quickcheck! {
+    fn prop_dedup_construction(first: Vec<u8>, second: Vec<u8>) -> bool {
+        let mut list = SortedList::from_iter_dedup(first.iter().cloned());
+        let mut expected = first.clone();
+        expected.sort();
+        expected.dedup();
+        let built = list.iter().eq(expected.iter()) && list.chunks.check();
+
+        // Duplicates already in the list are kept.
+        list.add(0);
+        list.add(0);
+        expected.push(0);
+        expected.push(0);
+        list.add_all_dedup(second.iter().cloned());
+        let mut new = second.clone();
+        new.sort();
+        new.dedup();
+        new.retain(|x| !expected.contains(x));
+        expected.extend(new);
+        expected.sort();
+        built && list.iter().eq(expected.iter()) && list.chunks.check()
+    }
+}
+
+#[test]
+fn zero_sized_elements() {
+    use SortedRead;
+
+    let mut list = SortedList::with_growth_policy(LoadFactor(4));
+    for _ in 0..100 {
+        list.add(());
+    }
+    assert_eq!(100, list.len());
+    assert!(list.chunks.check());
+    assert!(list.chunks.lists.len() > 1);
+    assert_eq!(100, list.iter().count());
+    assert_eq!(100, list.range(..).count());
+    assert_eq!((100, Some(100)), list.range(()..=()).size_hint());
+    assert_eq!(0, list.rank(&()));
+    assert_eq!(Some(&()), SortedRead::get(&list, 99));
+    assert_eq!(None, SortedRead::get(&list, 100));
+    assert_eq!(&(), list.index_from_end(99));
+    assert_eq!(Some(0), list.remove_with_index(&()));
+    assert_eq!(50, list.remove_indices(0..50).len());
+    assert!(list.has_duplicates());
+    list.rebalance();
+    assert_eq!(0.0, list.fragmentation());
+
+    list.dedup_by(|_, _| true);
+    assert_eq!(1, list.len());
+    assert!(list.chunks.check());
+    assert_eq!(Some(()), list.pop_last());
+    assert_eq!(None, list.pop_first());
+    assert!(list.is_empty());
+
+    let collected: SortedList<()> = std::iter::repeat_n((), 5000).collect();
+    assert_eq!(5000, collected.len());
+    assert_eq!(5000, collected.into_iter().count());
+}
+
+#[test]
+fn collect_references() {
+    let map: std::collections::BTreeMap<u32, u32> = (0..3000).map(|x| (x, 3000 - x)).collect();
+    let list: SortedList<u32> = map.values().collect();
+    assert!(list.iter().cloned().eq(1..=3000));
+}
+
+#[test]
+fn with_load_factor() {
+    let mut list = SortedList::with_load_factor(16);
+    list.extend(0..1000);
+    assert!(list.chunks.check());
+    assert!(list
+        .chunks
+        .lists
+        .iter()
+        .all(|l| 8 <= l.len() && l.len() < 32));
+}
+
+#[test]
+fn with_capacity() {
+    let mut list = SortedList::with_capacity(5000);
+    list.extend((0..5000).rev());
+    assert!(list.chunks.check());
+    assert!(list.iter().cloned().eq(0..5000));
+    list.reserve(0);
+    assert_eq!(5000, list.len());
+}
+
+#[test]
+fn shrink_to_fit() {
+    let mut list: SortedList<u32> = (0..10_000).collect();
+    for _ in 0..9000 {
+        list.pop_last();
+    }
+    assert!(list.capacity() > 1000);
+    list.shrink_to_fit();
+    assert_eq!(1000, list.capacity());
+    assert!(list.chunks.check() && list.iter().cloned().eq(0..1000));
+}
+
+#[test]
+#[should_panic(expected = "load factor must be at least 1")]
+fn with_load_factor_zero() {
+    SortedList::<u32>::with_load_factor(0);
+}
+
+#[test]
+fn extend() {
+    let mut list = SortedList::with_growth_policy(LoadFactor(4));
+    list.extend((0..100).rev());
+    list.extend(vec![50, 50]);
+    assert_eq!(102, list.len());
+    assert_eq!(3, list.count(&50));
+    assert!(list.chunks.check());
+}
+
+quickcheck! {
+    fn prop_nearest_k(vals: Vec<i16>, query: i16, k: usize) -> bool {
+        let mut expected: Vec<i32> = vals.into_iter().map(i32::from).collect();
+        let list = small_chunk_list(&expected);
+        let query = i32::from(query);
+        let k = k % (expected.len() + 2);
+        let nearest: Vec<i32> = list.nearest_k(&query, k).cloned().collect();
+
+        // Nearest first, with ties broken towards the lower value.
+        expected.sort_by_key(|&x| ((x - query).abs(), x));
+        expected.truncate(k);
+        nearest == expected && list.nearest_k(&query, k).next() == list.closest(&query).filter(|_| k > 0)
+    }
+}
+
+#[test]
+fn version() {
+    let mut list = SortedList::with_growth_policy(LoadFactor(4));
+    let mut seen = vec![list.version()];
+    let mut changed = |list: &SortedList<u32>| {
+        let changed = list.version() > *seen.last().unwrap();
+        seen.push(list.version());
+        changed
+    };
+    for x in 0..50 {
+        list.add(x);
+    }
+    assert!(changed(&list));
+    assert_eq!(Some(0), list.remove_with_index(&0));
+    assert!(changed(&list));
+    assert_eq!(None, list.remove_with_index(&0));
+    let _ = list.contains(&3);
+    let _ = list.iter().count();
+    list.rebalance();
+    assert!(!changed(&list));
+    list.pop_last();
+    assert!(changed(&list));
+    list.iter_mut_guarded();
+    assert!(changed(&list));
+    list.dedup();
+    assert!(changed(&list));
+    list.take_all();
+    assert!(changed(&list));
+    assert!(list.is_empty() && list.version() > 0);
+}
+
+quickcheck! {
+    fn prop_remove_and_take(vals: Vec<u8>, removed: Vec<u8>) -> bool {
+        let mut expected: Vec<u8> = vals.iter().map(|x| x % 16).collect();
+        let mut list = small_chunk_list(&expected);
+        expected.sort();
+        for (n, &x) in removed.iter().enumerate() {
+            let x = x % 16;
+            let position = expected.iter().position(|&y| y == x);
+            if let Some(p) = position {
+                expected.remove(p);
+            }
+            let found = if n % 2 == 0 {
+                list.take(&x) == position.map(|_| x)
+            } else {
+                list.remove(&x) == position.is_some()
+            };
+            if !found || !list.chunks.check() {
+                return false;
+            }
+        }
+        list.iter().eq(expected.iter())
+    }
+}
+
+quickcheck! {
+    fn prop_remove_all(vals: Vec<u8>, removed: Vec<u8>) -> bool {
+        let mut expected: Vec<u8> = vals.iter().map(|x| x % 8).collect();
+        let mut list = small_chunk_list(&expected);
+        expected.sort();
+        for &x in &removed {
+            let x = x % 8;
+            let before = expected.len();
+            expected.retain(|&y| y != x);
+            if list.remove_all(&x) != before - expected.len() || !list.chunks.check() {
+                return false;
+            }
+        }
+        list.iter().eq(expected.iter())
+    }
+}
+
+quickcheck! {
+    fn prop_remove_index(vals: Vec<u8>, removed: Vec<usize>) -> bool {
+        let mut list = small_chunk_list(&vals);
+        let mut expected = vals.clone();
+        expected.sort();
+        for &i in &removed {
+            let i = i % (vals.len() + 1);
+            let removed = if i < expected.len() {
+                Some(expected.remove(i))
+            } else {
+                None
+            };
+            if list.try_remove_index(i) != removed || !list.chunks.check() {
+                return false;
+            }
+        }
+        list.iter().eq(expected.iter())
+    }
+}
+
+#[test]
+#[should_panic(expected = "index 3 out of range for list of length 3")]
+fn remove_index_out_of_bounds() {
+    let mut list: SortedList<u32> = (0..4).collect();
+    assert_eq!(2, list.remove_index(2));
+    list.remove_index(3);
+}
+
+quickcheck! {
+    fn prop_get(vals: Vec<u8>, k: usize) -> bool {
+        let list = small_chunk_list(&vals);
+        let mut sorted = vals;
+        sorted.sort();
+        let k = k % (sorted.len() + 2);
+        let from_end = sorted.len().checked_sub(k + 1);
+        list.get(k) == sorted.get(k)
+            && list.get_from_end(k) == from_end.map(|k| &sorted[k])
+            && (0..sorted.len()).all(|k| list.get(k) == Some(&list[k]))
+            && sorted.iter().rev().enumerate().all(|(k, x)| list.index_from_end(k) == x)
+    }
+}
+
+quickcheck! {
+    fn prop_search(vals: Vec<u8>, probe: u8) -> bool {
+        let mut sorted: Vec<u8> = vals.iter().map(|x| x % 16).collect();
+        let list = small_chunk_list(&sorted);
+        sorted.sort();
+        let probe = probe % 17;
+        let first = sorted.iter().position(|&x| x == probe);
+        let last = sorted.iter().rposition(|&x| x == probe);
+        let (left, right) = (
+            sorted.partition_point(|&x| x < probe),
+            sorted.partition_point(|&x| x <= probe),
+        );
+        list.index_of(&probe) == first
+            && list.first_index_of(&probe) == first
+            && list.last_index_of(&probe) == last
+            && list.count(&probe) == right - left
+            && list.bisect_left(&probe) == left
+            && list.bisect_right(&probe) == right
+            && list.equal_range(&probe) == (left..right)
+            && list.find_lt(&probe) == sorted.iter().rev().find(|&&x| x < probe)
+            && list.find_le(&probe) == sorted.iter().rev().find(|&&x| x <= probe)
+            && list.find_gt(&probe) == sorted.iter().find(|&&x| x > probe)
+            && list.find_ge(&probe) == sorted.iter().find(|&&x| x >= probe)
+    }
+}
+
+quickcheck! {
+    fn prop_range(vals: Vec<u8>, a: u8, b: u8) -> bool {
+        use std::ops::Bound::{Excluded, Included, Unbounded};
+        let mut sorted: Vec<u8> = vals.iter().map(|x| x % 32).collect();
+        let list = small_chunk_list(&sorted);
+        sorted.sort();
+        let (a, b) = (a % 34, b % 34);
+        let expect = |f: &dyn Fn(u8) -> bool| sorted.iter().filter(|&&x| f(x)).count();
+        let iter_from = list.iter_from(&a);
+        list.range(a..b).count() == expect(&|x| a <= x && x < b)
+            && list.range(a..=b).count() == expect(&|x| a <= x && x <= b)
+            && list.range((Excluded(a), Unbounded)).count() == expect(&|x| a < x)
+            && list.range(..b).count() == expect(&|x| x < b)
+            && list.range((Included(a), Included(b))).eq(sorted.iter().filter(|&&x| a <= x && x <= b))
+            && list.count_in_range(a..b) == expect(&|x| a <= x && x < b)
+            && list.count_in_range(..=b) == expect(&|x| x <= b)
+            && iter_from.len() == expect(&|x| a <= x)
+            && iter_from.eq(sorted.iter().filter(|&&x| a <= x))
+    }
+}
+
+quickcheck! {
+    fn prop_drain(vals: Vec<u16>, taken: usize) -> bool {
+        let mut list = small_chunk_list(&vals);
+        let mut sorted = vals.clone();
+        sorted.sort();
+        let taken = taken % (vals.len() + 1);
+
+        let mut drain = list.drain();
+        let len_ok = drain.len() == vals.len();
+        let drained: Vec<u16> = drain.by_ref().take(taken).collect();
+        let rest_ok = drain.len() == vals.len() - taken;
+        drop(drain);
+        let emptied = list.is_empty() && list.chunks.lists.len() == 1 && list.chunks.check();
+        list.add(7);
+        len_ok && rest_ok && emptied && drained == sorted[..taken] && list.len() == 1
+    }
+}
+
+#[test]
+fn drain_keeps_allocation() {
+    let mut list: SortedList<u32> = (0..5000).collect();
+    let capacity = list.chunks.lists[0].capacity();
+    assert!(list.drain().eq(0..5000));
+    assert_eq!(capacity, list.chunks.lists[0].capacity());
+
+    let mut list: SortedList<u32> = (0..10).collect();
+    std::mem::forget(list.drain());
+    assert!(list.is_empty());
+    list.add(1);
+    assert!(list.iter().eq([1].iter()));
+}
+
+quickcheck! {
+    fn prop_drain_range(vals: Vec<u8>, a: u8, b: u8) -> bool {
+        let mut list = small_chunk_list(&vals);
+        let mut sorted = vals;
+        sorted.sort();
+        let drained: Vec<u8> = list.drain_range(a..=b).collect();
+        let (inside, outside): (Vec<u8>, Vec<u8>) =
+            sorted.into_iter().partition(|&x| a <= x && x <= b);
+        drained == inside && list.iter().eq(outside.iter()) && list.chunks.check()
+    }
+
+    fn prop_remove_range(vals: Vec<u8>, a: usize, b: usize) -> bool {
+        let mut list = small_chunk_list(&vals);
+        let mut sorted = vals;
+        sorted.sort();
+        let (a, b) = (a % (sorted.len() + 1), b % (sorted.len() + 1));
+        let range = a.min(b)..a.max(b);
+        sorted.drain(range.clone());
+        list.remove_range(range);
+        list.iter().eq(sorted.iter()) && list.chunks.check()
+    }
+
+    fn prop_order_statistics(vals: Vec<u8>, p: u8) -> bool {
+        let list = small_chunk_list(&vals);
+        let mut sorted = vals;
+        sorted.sort();
+        let p = f64::from(p % 101);
+        let at_or_below = |x: &u8| sorted.iter().filter(|&y| y <= x).count() as f64;
+        let percentile_ok = match list.percentile(p) {
+            Some(x) => {
+                at_or_below(x) >= p / 100.0 * sorted.len() as f64
+                    && sorted.iter().filter(|&y| y < x).all(|y| {
+                        at_or_below(y) < p / 100.0 * sorted.len() as f64
+                    })
+            }
+            None => sorted.is_empty(),
+        };
+        percentile_ok
+            && list.kth(sorted.len() / 3) == sorted.get(sorted.len() / 3)
+            && list.median() == sorted.get(sorted.len().saturating_sub(1) / 2)
+            && list.percentile(100.0) == sorted.last()
+    }
+
+    fn prop_split(vals: Vec<u8>, at: u8, i: usize) -> bool {
+        let mut sorted = vals.clone();
+        sorted.sort();
+        let mut by_value = small_chunk_list(&vals);
+        let tail_by_value = by_value.split_off(&at);
+        let split = sorted.partition_point(|&x| x < at);
+        let mut by_index = small_chunk_list(&vals);
+        let i = i % (vals.len() + 1);
+        let tail_by_index = by_index.split_at_index(i);
+        [(by_value, tail_by_value, split), (by_index, tail_by_index, i)]
+            .iter()
+            .all(|(head, tail, k)| {
+                head.iter().eq(sorted[..*k].iter())
+                    && tail.iter().eq(sorted[*k..].iter())
+                    && head.chunks.check()
+                    && tail.chunks.check()
+            })
+    }
+}
+
+#[test]
+#[should_panic(expected = "percentile 101 is not between 0 and 100")]
+fn percentile_out_of_range() {
+    let list: SortedList<u8> = vec![1, 2, 3].into_iter().collect();
+    list.percentile(101.0);
+}
+
+#[test]
+#[should_panic(expected = "range end 5 out of range for list of length 3")]
+fn remove_range_past_end() {
+    let mut list: SortedList<u8> = vec![1, 2, 3].into_iter().collect();
+    list.remove_range(1..5);
+}
+
+#[test]
+#[should_panic(expected = "index 4 out of range for list of length 3")]
+fn split_at_index_past_end() {
+    let mut list: SortedList<u8> = vec![1, 2, 3].into_iter().collect();
+    list.split_at_index(4);
+}
+
+#[test]
+fn clear() {
+    let mut list = SortedList::with_growth_policy(LoadFactor(4));
+    for x in 0..100 {
+        list.add(x);
+    }
+    let n_lists = list.chunks.lists.len();
+    list.clear_keep_capacity();
+    assert!(list.is_empty() && list.chunks.check());
+    assert_eq!(1, list.chunks.lists.len());
+    for x in (0..100).rev() {
+        list.add(x);
+    }
+    assert!(list.iter().cloned().eq(0..100) && list.chunks.check());
+    assert!(list.chunks.lists.len() >= n_lists / 2);
+
+    list.clear();
+    assert!(list.is_empty() && list.chunks.check());
+    assert_eq!(None, list.first());
+    list.add(3);
+    assert!(list.iter().eq([3].iter()));
+}
@@ -0,0 +1,220 @@
+//! All-or-nothing batches of changes to a sorted list.
+//!
+//! `SortedList::transaction` stages additions and removals made through a `Transaction`, and only
+//! applies them if the closure returns `Ok`. The staged changes are applied together, with a
+//! single pass to rebalance the sublists, and the list is left untouched on `Err`.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::SortedList;
+//! let mut list: SortedList<u32> = (0..10).collect();
+//!
+//! let result: Result<(), &str> = list.transaction(|txn| {
+//!     txn.add(20);
+//!     if !txn.remove(&99) {
+//!         return Err("99 is missing");
+//!     }
+//!     Ok(())
+//! });
+//! assert!(result.is_err());
+//! assert_eq!(10, list.len());
+//!
+//! let result: Result<(), &str> = list.transaction(|txn| {
+//!     txn.add(20);
+//!     txn.remove(&0);
+//!     Ok(())
+//! });
+//! assert!(result.is_ok());
+//! assert_eq!(Some(&1), list.first());
+//! assert_eq!(Some(&20), list.last());
+//! ```
+
+use super::SortedList;
+use std::collections::BTreeMap;
+
+/// Changes staged against a `SortedList`, created by `SortedList::transaction`.
+///
+/// Staging an addition or removal costs `O(log k)` on top of the list's own search, where `k` is
+/// the number of changes staged so far.
+#[derive(Debug)]
+pub struct Transaction<'a, T: Ord> {
+    list: &'a SortedList<T>,
+    // The first staged copy of each value is its key, and any later copies are kept alongside.
+    adds: BTreeMap<T, Vec<T>>,
+    // The number of copies of a value staged for removal, keyed by the index of its first copy
+    // in `list`. Indices can't change while the list is borrowed.
+    removes: BTreeMap<usize, usize>,
+    len: usize,
+}
+
+impl<'a, T: Ord> Transaction<'a, T> {
+    /// Stages a value to be added.
+    pub fn add(&mut self, val: T) {
+        self.len += 1;
+        match self.adds.get_mut(&val) {
+            Some(copies) => copies.push(val),
+            None => {
+                self.adds.insert(val, Vec::new());
+            }
+        }
+    }
+
+    /// Stages the removal of one copy of `val`, returning whether there was a copy to remove.
+    ///
+    /// Values added earlier in the transaction are removed before those already in the list.
+    pub fn remove(&mut self, val: &T) -> bool {
+        if let Some(copies) = self.adds.get_mut(val) {
+            if copies.pop().is_none() {
+                self.adds.remove(val);
+            }
+            self.len -= 1;
+            return true;
+        }
+
+        let (start, end) = (
+            self.list.bisect_left_indices(val),
+            self.list.bisect_right_indices(val),
+        );
+        let (start, end) = (
+            self.list.chunks.flat_index(start.0, start.1),
+            self.list.chunks.flat_index(end.0, end.1),
+        );
+        let staged = self.removes.get(&start).cloned().unwrap_or(0);
+        if start + staged < end {
+            self.removes.insert(start, staged + 1);
+            self.len -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The length the list will have if the transaction is committed.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Ord> SortedList<T> {
+    /// Runs `f` to stage changes to the list, applying them only if it returns `Ok`.
+    ///
+    /// Whatever `f` returns is passed back. The staged changes are applied with one pass over the
+    /// sublists for removals and one merge for additions, so a large batch costs about the same
+    /// as rebuilding the list once.
+    pub fn transaction<R, E, F>(&mut self, f: F) -> Result<R, E>
+    where
+        F: FnOnce(&mut Transaction<'_, T>) -> Result<R, E>,
+    {
+        let mut txn = Transaction {
+            list: self,
+            adds: BTreeMap::new(),
+            removes: BTreeMap::new(),
+            len: self.len(),
+        };
+        let rv = f(&mut txn)?;
+        let Transaction { adds, removes, .. } = txn;
+
+        if !removes.is_empty() {
+            self.chunks.bump_version();
+        }
+        // Runs of equal values don't overlap, so their indices come out in order.
+        let mut removes = removes
+            .into_iter()
+            .flat_map(|(start, n)| start..start + n)
+            .peekable();
+        let mut k = 0;
+        for list in &mut self.chunks.lists {
+            list.retain(|_| {
+                k += 1;
+                removes.next_if_eq(&(k - 1)).is_none()
+            });
+        }
+
+        if adds.is_empty() {
+            self.chunks.normalize();
+        } else {
+            self.merge_iter(
+                adds.into_iter()
+                    .flat_map(|(first, rest)| std::iter::once(first).chain(rest)),
+            );
+        }
+        Ok(rv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use LoadFactor;
+
+    fn small_list(vals: &[u8]) -> SortedList<u8> {
+        let mut list = SortedList::with_growth_policy(LoadFactor(4));
+        for &x in vals {
+            list.add(x);
+        }
+        list
+    }
+
+    #[test]
+    fn rollback_leaves_list_untouched() {
+        let mut list = small_list(&[1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let lists = list.chunks.lists.clone();
+        let result: Result<(), ()> = list.transaction(|txn| {
+            txn.add(0);
+            assert!(txn.remove(&5));
+            Err(())
+        });
+        assert_eq!(Err(()), result);
+        assert_eq!(lists, list.chunks.lists);
+        assert_eq!(9, list.len());
+    }
+
+    #[test]
+    fn removes_count_copies() {
+        let mut list = small_list(&[1, 2, 2, 2, 3]);
+        let result: Result<usize, ()> = list.transaction(|txn| {
+            assert!(txn.remove(&2));
+            assert!(txn.remove(&2));
+            txn.add(2);
+            assert!(txn.remove(&2));
+            assert!(txn.remove(&2));
+            assert!(!txn.remove(&2));
+            assert!(!txn.remove(&7));
+            Ok(txn.len())
+        });
+        assert_eq!(Ok(2), result);
+        assert!(list.iter().eq([1, 3].iter()));
+    }
+
+    quickcheck! {
+        fn prop_commit_matches_sequential(vals: Vec<u8>, adds: Vec<u8>, removes: Vec<u8>) -> bool {
+            let mut list = small_list(&vals);
+            let mut expected = vals;
+            expected.extend(adds.iter().cloned());
+            let result: Result<(), ()> = list.transaction(|txn| {
+                for &x in &adds {
+                    txn.add(x);
+                }
+                for x in &removes {
+                    let k = expected.iter().position(|y| y == x);
+                    if txn.remove(x) != k.is_some() {
+                        return Err(());
+                    }
+                    if let Some(k) = k {
+                        expected.swap_remove(k);
+                    }
+                }
+                Ok(())
+            });
+            expected.sort();
+            result.is_ok()
+                && list.len() == expected.len()
+                && list.iter().eq(expected.iter())
+                && list.chunks.lists.iter().all(|l| l.len() < 8)
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! A trait for read-only queries on sorted collections.
+//!
+//! Code that only needs to look things up can be written against `SortedRead` and work with any
+//! sorted backend, whether that's a `SortedList`, a `FrozenSortedList`, a plain sorted slice, or a
+//! memory mapping.
+
+use super::sorted_utils::slice_range;
+use std::ops::RangeBounds;
+
+/// The read-only query interface shared by the sorted collections in this crate.
+///
+/// Positions are ranks in sorted order, starting at zero.
+pub trait SortedRead<T: Ord> {
+    type Iter<'a>: Iterator<Item = &'a T>
+    where
+        Self: 'a,
+        T: 'a;
+    type Range<'a>: Iterator<Item = &'a T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The element at position `i`, or `None` if it's out of bounds.
+    fn get(&self, i: usize) -> Option<&T>;
+
+    fn contains(&self, val: &T) -> bool;
+
+    /// The number of elements less than `val`.
+    fn rank(&self, val: &T) -> usize;
+
+    /// Iterates over the elements within a range of values, in order.
+    fn range<R: RangeBounds<T>>(&self, range: R) -> Self::Range<'_>;
+
+    /// Iterates over all of the elements, in order.
+    fn iter(&self) -> Self::Iter<'_>;
+}
+
+/// Slices are queried with binary search. It is a logic error to query a slice that isn't sorted.
+impl<T: Ord> SortedRead<T> for [T] {
+    type Iter<'a>
+        = std::slice::Iter<'a, T>
+    where
+        T: 'a;
+    type Range<'a>
+        = std::slice::Iter<'a, T>
+    where
+        T: 'a;
+
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        <[T]>::get(self, i)
+    }
+
+    fn contains(&self, val: &T) -> bool {
+        self.binary_search(val).is_ok()
+    }
+
+    fn rank(&self, val: &T) -> usize {
+        self.partition_point(|x| x < val)
+    }
+
+    fn range<R: RangeBounds<T>>(&self, range: R) -> std::slice::Iter<'_, T> {
+        self[slice_range(self, &range)].iter()
+    }
+
+    fn iter(&self) -> std::slice::Iter<'_, T> {
+        <[T]>::iter(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SortedList;
+
+    /// Exercises a backend through the trait alone, against a sorted vector of the same values.
+    fn check<S: SortedRead<u32> + ?Sized>(sorted: &S, expected: &[u32]) {
+        assert_eq!(expected.len(), sorted.len());
+        assert_eq!(expected.is_empty(), sorted.is_empty());
+        assert!(sorted.iter().eq(expected.iter()));
+        for i in 0..expected.len() + 1 {
+            assert_eq!(expected.get(i), sorted.get(i));
+        }
+        for x in 0..20 {
+            assert_eq!(expected.contains(&x), sorted.contains(&x));
+            assert_eq!(expected.iter().filter(|y| **y < x).count(), sorted.rank(&x));
+            assert!(sorted
+                .range(x..x + 5)
+                .eq(expected.iter().filter(|y| (x..x + 5).contains(*y))));
+        }
+    }
+
+    #[test]
+    fn backends_agree() {
+        let vals = vec![1, 3, 3, 4, 8, 12, 12, 12, 15];
+        check(&vals[..], &vals);
+        check(&vals.iter().cloned().collect::<SortedList<u32>>(), &vals);
+        check(&[][..], &[]);
+
+        let big: Vec<u32> = (0..3000).map(|x| x / 200).collect();
+        check(&big.iter().cloned().collect::<SortedList<u32>>(), &big);
+        check(&SortedList::default(), &[]);
+        check(
+            &vals.iter().cloned().collect::<SortedList<u32>>().freeze(),
+            &vals,
+        );
+    }
+}
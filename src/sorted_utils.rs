@@ -1,34 +1,59 @@
 //! Common code for sorted and unsorted variants of the list.
 
 use std::cmp::Ordering;
+use std::ops::{Bound, Range, RangeBounds};
 
 /// if the list size grows greater than the load factor, we split it.
 /// If the list size shrinks below the load factor, we join two lists.
 pub const DEFAULT_LOAD_FACTOR: usize = 1000;
 
 /// Inserts into a list while maintaining a preexisting ordering.
-pub fn insert_sorted<T: Ord>(vec: &mut Vec<T>, val: T) {
+///
+/// returns the index the value was inserted at.
+pub fn insert_sorted<T: Ord>(vec: &mut Vec<T>, val: T) -> usize {
     match vec.binary_search(&val) {
-        Ok(i) | Err(i) => vec.insert(i, val),
+        Ok(i) | Err(i) => {
+            vec.insert(i, val);
+            i
+        }
     }
 }
 
-/// Inserts a value into a list of lists, as in SortedList.
+/// Resolves a range of values to the range of indices holding them in a sorted slice.
+///
+/// An inverted range of values gives an empty range of indices.
+pub fn slice_range<T: Ord, R: RangeBounds<T>>(slice: &[T], range: &R) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(x) => slice.partition_point(|y| y < x),
+        Bound::Excluded(x) => slice.partition_point(|y| y <= x),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(x) => slice.partition_point(|y| y <= x),
+        Bound::Excluded(x) => slice.partition_point(|y| y < x),
+        Bound::Unbounded => slice.len(),
+    };
+    start..end.max(start)
+}
+
+/// Finds the list that a value should be inserted into in a list of lists, as in SortedList.
 ///
-/// Does not handle empty sublists except for a single empty list.
-/// returns the index of the list that was inserted into.
-pub fn insert_list_of_lists<T: Ord>(list_list: &mut Vec<Vec<T>>, val: T) -> usize {
+/// Elements are compared with `cmp`, which must agree with `Ord`; it lets callers count
+/// comparisons. Does not handle empty sublists except for a single empty list.
+pub fn find_list_of_lists<T, F>(list_list: &[Vec<T>], val: &T, mut cmp: F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
     if list_list.len() == 1 && list_list[0].is_empty() {
-        list_list[0].push(val);
         return 0;
     }
 
-    let list_i = match list_list.binary_search_by(|list| {
+    match list_list.binary_search_by(|list| {
         let first = list.first().unwrap();
         let last = list.last().unwrap();
-        if val > *last {
+        if cmp(val, last) == Ordering::Greater {
             Ordering::Less
-        } else if val < *first {
+        } else if cmp(val, first) == Ordering::Less {
             Ordering::Greater
         } else {
             Ordering::Equal
@@ -37,10 +62,74 @@ pub fn insert_list_of_lists<T: Ord>(list_list: &mut Vec<Vec<T>>, val: T) -> usiz
         Ok(i) => i,
         Err(0) => 0,
         Err(n) => n - 1, // TODO: how fair is this?
-    };
+    }
+}
+
+/// Merges the sorted values of `vals` into the sorted `vec`.
+///
+/// Values from `vec` come before equal values from `vals`.
+pub fn merge_sorted<T: Ord>(vec: &mut Vec<T>, vals: Vec<T>) {
+    if vals.is_empty() {
+        return;
+    }
+
+    let old = std::mem::replace(vec, Vec::with_capacity(vec.len() + vals.len()));
+    let mut old = old.into_iter().peekable();
+    let mut vals = vals.into_iter().peekable();
+    loop {
+        let next = match (old.peek(), vals.peek()) {
+            (Some(x), Some(y)) if y < x => vals.next(),
+            (Some(_), _) => old.next(),
+            (None, Some(_)) => vals.next(),
+            (None, None) => break,
+        };
+        vec.extend(next);
+    }
+}
 
-    insert_sorted(&mut list_list[list_i], val);
-    list_i
+/// Merges the sorted, distinct values of `vals` into the sorted, distinct `vec`, keeping it
+/// distinct. When a value is in both, the one from `vec` is kept.
+pub fn merge_distinct<T: Ord>(vec: &mut Vec<T>, vals: Vec<T>) {
+    if vals.is_empty() {
+        return;
+    }
+
+    let old = std::mem::replace(vec, Vec::with_capacity(vec.len() + vals.len()));
+    let mut old = old.into_iter().peekable();
+    let mut vals = vals.into_iter().peekable();
+    loop {
+        let next = match (old.peek(), vals.peek()) {
+            (Some(x), Some(y)) if y < x => vals.next(),
+            (Some(x), Some(y)) if y == x => {
+                vals.next();
+                old.next()
+            }
+            (Some(_), _) => old.next(),
+            (None, Some(_)) => vals.next(),
+            (None, None) => break,
+        };
+        vec.extend(next);
+    }
+}
+
+/// Collects the distinct values of an iterator in sorted order.
+///
+/// Values are sorted and deduplicated in batches, which are merged as they go, so duplicates
+/// never take up more than a batch's worth of memory. Each batch is at least as large as the
+/// values gathered so far, which keeps the total merging work to `O(n log n)`.
+pub fn sorted_distinct<T: Ord, I: IntoIterator<Item = T>>(iter: I) -> Vec<T> {
+    const MIN_BATCH: usize = 4096;
+    let mut iter = iter.into_iter();
+    let mut vals = Vec::new();
+    loop {
+        let mut batch: Vec<T> = iter.by_ref().take(vals.len().max(MIN_BATCH)).collect();
+        if batch.is_empty() {
+            return vals;
+        }
+        batch.sort();
+        batch.dedup();
+        merge_distinct(&mut vals, batch);
+    }
 }
 
 #[cfg(test)]
@@ -55,4 +144,26 @@ pub mod tests {
         insert_sorted(&mut vec, -1000);
         assert_eq!(vec![-1000, 22], vec);
     }
+
+    #[test]
+    fn test_merge_sorted() {
+        let mut vec = vec![1, 3, 5];
+        merge_sorted(&mut vec, vec![0, 3, 4, 9]);
+        assert_eq!(vec![0, 1, 3, 3, 4, 5, 9], vec);
+
+        let mut empty = vec![];
+        merge_sorted(&mut empty, vec![2, 2]);
+        assert_eq!(vec![2, 2], empty);
+    }
+
+    #[test]
+    fn test_sorted_distinct() {
+        let mut vec = vec![1, 3, 5];
+        merge_distinct(&mut vec, vec![0, 3, 4, 9]);
+        assert_eq!(vec![0, 1, 3, 4, 5, 9], vec);
+
+        let vals = sorted_distinct((0..20000).map(|x| (x * 7919) % 5000));
+        assert!(vals.into_iter().eq(0..5000));
+        assert!(sorted_distinct(Vec::<u8>::new()).is_empty());
+    }
 }
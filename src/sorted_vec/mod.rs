@@ -0,0 +1,348 @@
+//! Module for a sorted list stored in a single vector.
+//!
+//! For small and medium sized collections, a single vector is faster than a `SortedList`, since
+//! there is no second level to search. `SortedVec` has the same method names as `SortedList`,
+//! so switching between the two only means changing the type.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::SortedVec;
+//! let mut list: SortedVec<i32> = SortedVec::new();
+//!
+//! list.add(13);
+//! list.add(3);
+//!
+//! assert_eq!(2, list.len());
+//! assert!(list.contains(&3));
+//! assert_eq!(Some(&3), list.first());
+//! assert_eq!(&[3, 13], list.as_slice());
+//! ```
+
+#[cfg(test)]
+mod tests;
+
+use super::sorted_read::SortedRead;
+use super::sorted_utils::{insert_sorted, merge_sorted, slice_range};
+use super::sorted_write::SortedWrite;
+use std::cmp::Ordering;
+use std::iter::FromIterator;
+use std::ops::{Index, RangeBounds};
+
+/// A sorted list backed by one contiguous vector.
+///
+/// Inserting is `O(n)`, so prefer `SortedList` for large collections.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SortedVec<T: Ord> {
+    vals: Vec<T>,
+}
+
+impl<T: Ord> SortedVec<T> {
+    pub fn new() -> Self {
+        Self { vals: Vec::new() }
+    }
+
+    pub fn contains(&self, val: &T) -> bool {
+        self.vals.binary_search(val).is_ok()
+    }
+
+    pub fn add(&mut self, new_val: T) {
+        insert_sorted(&mut self.vals, new_val);
+    }
+
+    /// Adds a value and returns the index it was inserted at.
+    pub fn add_with_index(&mut self, new_val: T) -> usize {
+        insert_sorted(&mut self.vals, new_val)
+    }
+
+    /// Removes one element equal to `val`, returning the index it was removed from, or `None`
+    /// if there was no such element.
+    pub fn remove_with_index(&mut self, val: &T) -> Option<usize> {
+        let i = self.vals.partition_point(|x| x < val);
+        if self.vals.get(i) == Some(val) {
+            self.vals.remove(i);
+            Some(i)
+        } else {
+            None
+        }
+    }
+
+    /// Removes one element equal to `val`, returning whether there was one.
+    pub fn remove(&mut self, val: &T) -> bool {
+        self.take(val).is_some()
+    }
+
+    /// Removes and returns one element equal to `val`, which may differ from `val` in ways that
+    /// `Ord` ignores, or returns `None` if there is no such element.
+    pub fn take(&mut self, val: &T) -> Option<T> {
+        let i = self.first_index_of(val)?;
+        Some(self.vals.remove(i))
+    }
+
+    /// Removes and returns the element at position `i`.
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn remove_index(&mut self, i: usize) -> T {
+        let len = self.len();
+        match self.try_remove_index(i) {
+            Some(val) => val,
+            None => panic!("index {} out of range for list of length {}", i, len),
+        }
+    }
+
+    /// Removes and returns the element at position `i`, or returns `None` if it is out of
+    /// bounds.
+    pub fn try_remove_index(&mut self, i: usize) -> Option<T> {
+        if i < self.len() {
+            Some(self.vals.remove(i))
+        } else {
+            None
+        }
+    }
+
+    /// The position at which `val` would be inserted before any equal elements, which is the
+    /// number of elements less than `val`.
+    pub fn bisect_left(&self, val: &T) -> usize {
+        self.vals.partition_point(|x| x < val)
+    }
+
+    /// The position at which `val` would be inserted after any equal elements, which is the
+    /// number of elements less than or equal to `val`.
+    pub fn bisect_right(&self, val: &T) -> usize {
+        self.vals.partition_point(|x| x <= val)
+    }
+
+    /// The greatest element less than `val`.
+    pub fn find_lt(&self, val: &T) -> Option<&T> {
+        self.bisect_left(val).checked_sub(1).map(|i| &self.vals[i])
+    }
+
+    /// The greatest element less than or equal to `val`.
+    pub fn find_le(&self, val: &T) -> Option<&T> {
+        self.bisect_right(val).checked_sub(1).map(|i| &self.vals[i])
+    }
+
+    /// The least element greater than `val`.
+    pub fn find_gt(&self, val: &T) -> Option<&T> {
+        self.vals.get(self.bisect_right(val))
+    }
+
+    /// The least element greater than or equal to `val`.
+    pub fn find_ge(&self, val: &T) -> Option<&T> {
+        self.vals.get(self.bisect_left(val))
+    }
+
+    /// The position of an element equal to `val`, or `None` if there is none. If there are
+    /// several, this is the first of them.
+    pub fn index_of(&self, val: &T) -> Option<usize> {
+        self.first_index_of(val)
+    }
+
+    /// The position of the first element equal to `val`.
+    pub fn first_index_of(&self, val: &T) -> Option<usize> {
+        let i = self.bisect_left(val);
+        self.vals.get(i).filter(|x| *x == val).map(|_| i)
+    }
+
+    /// The position of the last element equal to `val`.
+    pub fn last_index_of(&self, val: &T) -> Option<usize> {
+        let i = self.bisect_right(val).checked_sub(1)?;
+        Some(i).filter(|&i| self.vals[i] == *val)
+    }
+
+    /// The number of elements equal to `val`.
+    pub fn count(&self, val: &T) -> usize {
+        self.equal_range(val).len()
+    }
+
+    /// The positions of the elements equal to `val`. The range is empty, starting where `val`
+    /// would be inserted, if there are none.
+    pub fn equal_range(&self, val: &T) -> std::ops::Range<usize> {
+        self.bisect_left(val)..self.bisect_right(val)
+    }
+
+    /// Iterates over the elements within a range of values.
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> std::slice::Iter<'_, T> {
+        self.vals[slice_range(&self.vals, &range)].iter()
+    }
+
+    /// The number of elements within a range of values.
+    pub fn count_in_range<R: RangeBounds<T>>(&self, range: R) -> usize {
+        slice_range(&self.vals, &range).len()
+    }
+
+    /// The element at position `i`, or `None` if it is out of bounds.
+    pub fn get(&self, i: usize) -> Option<&T> {
+        self.vals.get(i)
+    }
+
+    /// The `k`th smallest element, counting from zero. This is the same as `get(k)`.
+    pub fn kth(&self, k: usize) -> Option<&T> {
+        self.get(k)
+    }
+
+    /// The middle element, or the lower of the two middle elements if the length is even.
+    pub fn median(&self) -> Option<&T> {
+        self.get(self.len().checked_sub(1)? / 2)
+    }
+
+    /// Merges values from an iterator, which must yield them in sorted order, into the list.
+    pub fn merge_iter<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let vals: Vec<T> = iter.into_iter().collect();
+        debug_assert!(vals.is_sorted(), "merge_iter was given unsorted values");
+        merge_sorted(&mut self.vals, vals);
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.vals.first()
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.vals.last()
+    }
+
+    pub fn pop_first(&mut self) -> Option<T> {
+        if self.vals.is_empty() {
+            None
+        } else {
+            Some(self.vals.remove(0))
+        }
+    }
+
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.vals.pop()
+    }
+
+    pub fn len(&self) -> usize {
+        self.vals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vals.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.vals.iter()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.vals
+    }
+
+    /// Binary searches the list with a comparator function, as `slice::binary_search_by`.
+    pub fn binary_search_by<F>(&self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        self.vals.binary_search_by(f)
+    }
+
+    /// Binary searches the list for an element whose key, as extracted by `f`, equals `b`.
+    pub fn binary_search_by_key<B, F>(&self, b: &B, f: F) -> Result<usize, usize>
+    where
+        B: Ord,
+        F: FnMut(&T) -> B,
+    {
+        self.vals.binary_search_by_key(b, f)
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.vals
+    }
+}
+
+impl<T: Ord> SortedRead<T> for SortedVec<T> {
+    type Iter<'a>
+        = std::slice::Iter<'a, T>
+    where
+        T: 'a;
+    type Range<'a>
+        = std::slice::Iter<'a, T>
+    where
+        T: 'a;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        self.get(i)
+    }
+
+    fn contains(&self, val: &T) -> bool {
+        self.contains(val)
+    }
+
+    fn rank(&self, val: &T) -> usize {
+        self.bisect_left(val)
+    }
+
+    fn range<R: RangeBounds<T>>(&self, range: R) -> std::slice::Iter<'_, T> {
+        self.range(range)
+    }
+
+    fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.iter()
+    }
+}
+
+impl<T: Ord> SortedWrite<T> for SortedVec<T> {
+    fn add(&mut self, val: T) {
+        self.add(val)
+    }
+
+    fn pop_first(&mut self) -> Option<T> {
+        self.pop_first()
+    }
+
+    fn pop_last(&mut self) -> Option<T> {
+        self.pop_last()
+    }
+
+    fn remove_with_index(&mut self, val: &T) -> Option<usize> {
+        self.remove_with_index(val)
+    }
+}
+
+impl<T: Ord> Index<usize> for SortedVec<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        &self.vals[i]
+    }
+}
+
+impl<T: Ord> IntoIterator for SortedVec<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> std::vec::IntoIter<T> {
+        self.vals.into_iter()
+    }
+}
+
+impl<T: Ord> Default for SortedVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Create a SortedVec from an Iterator.
+///
+/// The values are collected and then sorted once, in `O(n * log(n))`.
+impl<T: Ord> FromIterator<T> for SortedVec<T> {
+    fn from_iter<F>(iter: F) -> Self
+    where
+        F: IntoIterator<Item = T>,
+    {
+        let mut vals: Vec<T> = iter.into_iter().collect();
+        vals.sort();
+        Self { vals }
+    }
+}
+
+impl<T: Ord> From<Vec<T>> for SortedVec<T> {
+    fn from(mut vals: Vec<T>) -> Self {
+        vals.sort();
+        Self { vals }
+    }
+}
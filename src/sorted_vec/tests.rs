@@ -0,0 +1,112 @@
+use super::SortedVec;
+use sorted_read::SortedRead;
+use sorted_write::SortedWrite;
+use SortedList;
+
+#[test]
+fn basic_test() {
+    let mut list: SortedVec<i32> = SortedVec::default();
+    assert_eq!(None, list.first());
+
+    list.add(3);
+    list.add(13);
+    assert_eq!(1, list.add_with_index(5));
+
+    assert_eq!(&[3, 5, 13], list.as_slice());
+    assert_eq!(Some(&13), list.last());
+    assert_eq!(Some(1), list.remove_with_index(&5));
+    assert_eq!(None, list.remove_with_index(&5));
+    assert_eq!(Some(3), list.pop_first());
+    assert_eq!(Some(13), list.pop_last());
+    assert!(list.is_empty());
+}
+
+/// Calls the same inherent methods on either type, so switching types compiles unchanged.
+macro_rules! search_and_remove {
+    ($list:expr) => {{
+        let mut list = $list;
+        let positions = (
+            list.bisect_left(&3),
+            list.bisect_right(&3),
+            list.equal_range(&3),
+            list.count(&3),
+            list.index_of(&3),
+            list.last_index_of(&3),
+            list.count_in_range(..=3),
+        );
+        let neighbours = (
+            list.find_lt(&3).cloned(),
+            list.find_le(&3).cloned(),
+            list.find_gt(&3).cloned(),
+            list.find_ge(&3).cloned(),
+        );
+        let elements = (
+            list.get(1).cloned(),
+            list.kth(4).cloned(),
+            list.median().cloned(),
+            list.last().cloned(),
+            list.range(2..8).cloned().collect::<Vec<u32>>(),
+        );
+        let removed = (
+            list.remove(&3),
+            list.take(&3),
+            list.take(&4),
+            list.remove_index(0),
+            list.try_remove_index(10),
+        );
+        let rest = list.iter().cloned().collect::<Vec<u32>>();
+        (positions, neighbours, elements, removed, rest)
+    }};
+}
+
+#[test]
+fn same_methods_as_sorted_list() {
+    let vals = [7, 1, 3, 3, 3, 9];
+    let from_vec = search_and_remove!(vals.iter().cloned().collect::<SortedVec<u32>>());
+    let from_list = search_and_remove!(vals.iter().cloned().collect::<SortedList<u32>>());
+    assert_eq!(from_list, from_vec);
+    assert_eq!((1, 4, 1..4, 3, Some(1), Some(3), 4), from_vec.0);
+    assert_eq!((Some(1), Some(3), Some(7), Some(3)), from_vec.1);
+    assert_eq!(
+        (Some(3), Some(7), Some(3), Some(9), vec![3, 3, 3, 7]),
+        from_vec.2
+    );
+    assert_eq!((true, Some(3), None, 1, None), from_vec.3);
+    assert_eq!(vec![3, 7, 9], from_vec.4);
+}
+
+/// Generic code written against the traits runs the same on either representation.
+fn top_three<S: SortedRead<u32> + SortedWrite<u32>>(sorted: &mut S, vals: &[u32]) -> Vec<u32> {
+    for &x in vals {
+        sorted.add(x);
+        if sorted.len() > 3 {
+            sorted.pop_first();
+        }
+    }
+    sorted.iter().cloned().collect()
+}
+
+#[test]
+fn same_behaviour_as_sorted_list() {
+    let vals: Vec<u32> = (0..2000).map(|x| (x * 7919) % 2003).collect();
+    let from_vec = top_three(&mut SortedVec::new(), &vals);
+    let from_list = top_three(&mut SortedList::new(), &vals);
+    let mut sorted = vals.clone();
+    sorted.sort();
+    assert_eq!(sorted[sorted.len() - 3..].to_vec(), from_vec);
+    assert_eq!(from_list, from_vec);
+}
+
+quickcheck! {
+    fn prop_merge_iter(a: Vec<u8>, b: Vec<u8>) -> bool {
+        let mut list: SortedVec<u8> = a.iter().cloned().collect();
+        let mut b = b;
+        b.sort();
+        list.merge_iter(b.iter().cloned());
+
+        let mut expected = a;
+        expected.extend(b);
+        expected.sort();
+        list.into_vec() == expected
+    }
+}
@@ -0,0 +1,19 @@
+//! A trait for the basic mutations of sorted collections.
+
+/// The mutating operations shared by the sorted collections in this crate.
+///
+/// Together with `SortedRead`, this lets code switch between sorted representations without
+/// changing its call sites.
+pub trait SortedWrite<T: Ord> {
+    /// Adds a value, keeping the collection sorted.
+    fn add(&mut self, val: T);
+
+    /// Removes and returns the smallest value.
+    fn pop_first(&mut self) -> Option<T>;
+
+    /// Removes and returns the largest value.
+    fn pop_last(&mut self) -> Option<T>;
+
+    /// Removes one element equal to `val`, returning its index.
+    fn remove_with_index(&mut self, val: &T) -> Option<usize>;
+}
@@ -0,0 +1,86 @@
+//! Counters of the work a list does, for tuning.
+//!
+//! Requires the `stats` feature. Each list counts the sublists it splits and joins, the elements
+//! it shifts within sublists, and the comparisons made while searching, so the effect of a
+//! different `GrowthPolicy` can be measured directly rather than through timings.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::{LoadFactor, SortedList};
+//! let mut small = SortedList::with_growth_policy(LoadFactor(8));
+//! let mut large = SortedList::with_growth_policy(LoadFactor(512));
+//! for x in (0..2000).rev() {
+//!     small.add(x);
+//!     large.add(x);
+//! }
+//!
+//! assert!(small.op_stats().splits > large.op_stats().splits);
+//! assert!(small.op_stats().shifts < large.op_stats().shifts);
+//!
+//! small.reset_op_stats();
+//! assert_eq!(0, small.op_stats().comparisons);
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering::Relaxed};
+
+/// A snapshot of a list's operation counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpStats {
+    /// Sublists split in two.
+    pub splits: u64,
+    /// Sublists joined onto a neighbour.
+    pub merges: u64,
+    /// Elements moved along within a sublist by insertions and removals.
+    pub shifts: u64,
+    /// Comparisons between elements made while searching.
+    pub comparisons: u64,
+}
+
+/// The live counters. They are atomic so that searches through `&self` can count.
+#[derive(Debug, Default)]
+pub(crate) struct OpCounters {
+    splits: AtomicU64,
+    merges: AtomicU64,
+    shifts: AtomicU64,
+    comparisons: AtomicU64,
+}
+
+impl OpCounters {
+    pub(crate) const fn new() -> Self {
+        Self {
+            splits: AtomicU64::new(0),
+            merges: AtomicU64::new(0),
+            shifts: AtomicU64::new(0),
+            comparisons: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn add_splits(&self, n: usize) {
+        self.splits.fetch_add(n as u64, Relaxed);
+    }
+
+    pub(crate) fn add_merges(&self, n: usize) {
+        self.merges.fetch_add(n as u64, Relaxed);
+    }
+
+    pub(crate) fn add_shifts(&self, n: usize) {
+        self.shifts.fetch_add(n as u64, Relaxed);
+    }
+
+    pub(crate) fn add_comparisons(&self, n: usize) {
+        self.comparisons.fetch_add(n as u64, Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> OpStats {
+        OpStats {
+            splits: self.splits.load(Relaxed),
+            merges: self.merges.load(Relaxed),
+            shifts: self.shifts.load(Relaxed),
+            comparisons: self.comparisons.load(Relaxed),
+        }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
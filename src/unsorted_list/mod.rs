@@ -16,176 +16,492 @@
 //! assert_eq!(vec![3,-22,11], list.into_iter().collect::<Vec<i64>>());
 //! ```
 
-use super::sorted_utils::DEFAULT_LOAD_FACTOR;
+pub mod view;
+
+use super::chunked::ChunkedStorage;
+use super::growth::{GrowthPolicy, LoadFactor};
+#[cfg(feature = "stats")]
+use super::stats::OpStats;
 use super::{IntoIter, Iter};
+#[cfg(feature = "rand")]
+use rand::seq::{index, SliceRandom};
+#[cfg(feature = "rand")]
+use rand::Rng;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::collections::binary_heap::{BinaryHeap, PeekMut};
 use std::default::Default;
-use std::iter::FromIterator;
-use std::ops::{Index, IndexMut};
+use std::iter::{Extend, FromIterator, FusedIterator};
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Index, IndexMut, RangeBounds};
 
 /// An unsorted list.
 /// Usage is about the same as a vector.
 /// Performance should be better for large lists.
 #[derive(Debug)]
 pub struct UnsortedList<T> {
-    lists: Vec<Vec<T>>, // There is always at least one element in the outer list.
-    load_factor: usize,
-    len: usize,
+    chunks: ChunkedStorage<T>,
 }
 
 impl<T> UnsortedList<T> {
-    pub fn new() -> Self {
+    /// Creates an empty list. No memory is allocated until the first element is added.
+    pub const fn new() -> Self {
         Self {
-            lists: vec![Vec::new()],
-            load_factor: DEFAULT_LOAD_FACTOR,
-            len: 0,
+            chunks: ChunkedStorage::new(),
         }
     }
 
-    pub fn insert(&mut self, mut i: usize, element: T) {
-        let mut outer = 0;
-        // biases towards the earlier list.
-        while i > self.lists[outer].len() {
-            i -= self.lists[outer].len();
-            outer += 1;
+    /// Creates a list whose sublists are split and joined according to `policy`.
+    pub fn with_growth_policy<P: GrowthPolicy + 'static>(policy: P) -> Self {
+        Self {
+            chunks: ChunkedStorage::with_policy(Box::new(policy)),
         }
-
-        self.lists[outer].insert(i, element);
-        self.len += 1;
-        self.expand(outer);
     }
 
-    /// Splits sublists that are more than double the load level.
-    /// Updates the i when the sublist length is less than double the load
-    /// level. This requires incrementing the nodes in a traversal from the
-    /// leaf node to the root. For an example traversal see self._loc.
-    fn expand(&mut self, i: usize) {
-        // >= because otherwise contract can fail... better solution for this?
-        if self.lists[i].len() >= 2 * self.load_factor {
-            self.unchecked_expand(i)
-        }
+    /// Creates a list whose sublists are kept between half and double `load_factor` elements,
+    /// as with `SortedList::with_load_factor`. Panics if `load_factor` is zero.
+    pub fn with_load_factor(load_factor: usize) -> Self {
+        assert!(load_factor > 0, "load factor must be at least 1");
+        Self::with_growth_policy(LoadFactor(load_factor))
     }
 
-    fn unchecked_expand(&mut self, i: usize) {
-        let new_list = {
-            let inner = &mut self.lists[i];
-            let mid = inner.len() / 2;
-            inner.split_off(mid)
-        };
+    /// Creates an empty list with sublists already allocated for `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut list = Self::new();
+        list.reserve(capacity);
+        list
+    }
 
-        self.lists.insert(i + 1, new_list);
+    /// Allocates sublists for at least `additional` more elements, as
+    /// `SortedList::reserve` does.
+    pub fn reserve(&mut self, additional: usize) {
+        self.chunks.reserve(additional);
     }
 
-    // TODO: this can make lists that are too big.
-    fn contract(&mut self, i: usize) {
-        if self.lists.len() > 1 && self.lists[i].len() < self.load_factor / 2 {
-            self.unchecked_contract(i)
-        }
+    /// The work done by the list since it was created or its counts were reset.
+    #[cfg(feature = "stats")]
+    pub fn op_stats(&self) -> OpStats {
+        self.chunks.counters.snapshot()
     }
 
-    /// Contracts with the nearest list.
-    fn unchecked_contract(&mut self, i: usize) {
-        debug_assert!(self.len() > 1);
-        let (low, high) = self.contract_i(i);
-        let mut removed_list = self.lists.remove(high);
-        self.lists[low].append(&mut removed_list);
+    /// Sets all of the operation counts back to zero.
+    #[cfg(feature = "stats")]
+    pub fn reset_op_stats(&mut self) {
+        self.chunks.counters.reset();
     }
 
-    fn contract_i(&self, i: usize) -> (usize, usize) {
-        match i {
-            0 => (0, 1),
-            i if i == self.lists.len() => (self.lists.len() - 2, self.lists.len() - 1),
-            i => {
-                let other_list: usize = if self.lists[i - 1].len() < self.lists[i + 1].len() {
-                    i - 1
-                } else {
-                    i + 1
-                };
-                if i < other_list {
-                    (i, other_list)
-                } else {
-                    (other_list, i)
-                }
-            }
+    pub fn insert(&mut self, mut i: usize, element: T) {
+        self.chunks.bump_version();
+        self.chunks.ensure_sublist();
+        let mut outer = 0;
+        // biases towards the earlier list.
+        while i > self.chunks.lists[outer].len() {
+            i -= self.chunks.lists[outer].len();
+            outer += 1;
         }
+
+        self.chunks
+            .record_shifts(self.chunks.lists[outer].len() - i);
+        self.chunks.lists[outer].insert(i, element);
+        self.chunks.len += 1;
+        self.chunks.expand(outer);
     }
+
     pub fn first(&self) -> Option<&T> {
-        self.lists.first().and_then(|x| x.first())
+        self.chunks.first()
     }
 
     pub fn first_mut(&mut self) -> Option<&mut T> {
-        self.lists.first_mut().and_then(|x| x.first_mut())
+        self.chunks.bump_version();
+        self.chunks.first_mut()
     }
 
     pub fn last(&mut self) -> Option<&T> {
-        self.lists.last().and_then(|x| x.last())
+        self.chunks.last()
     }
 
     pub fn last_mut(&mut self) -> Option<&mut T> {
-        self.lists.last_mut().and_then(|x| x.last_mut())
+        self.chunks.bump_version();
+        self.chunks.last_mut()
     }
 
     pub fn pop_first(&mut self) -> Option<T> {
-        if self.is_empty() {
-            None
-        } else {
-            self.len -= 1;
-            let rv = Some(self.lists[0].remove(0));
-            self.contract(0);
-            rv
-        }
+        self.chunks.bump_version();
+        self.chunks.pop_first()
     }
 
     pub fn push(&mut self, element: T) {
-        self.lists.last_mut().unwrap().push(element);
-        self.len += 1;
-        let len = self.lists.len();
-        // FIXME catch with test?
-        self.contract(len);
+        self.chunks.bump_version();
+        self.chunks.ensure_sublist();
+        self.chunks.lists.last_mut().unwrap().push(element);
+        self.chunks.len += 1;
+        let last = self.chunks.lists.len() - 1;
+        self.chunks.expand(last);
     }
 
     pub fn pop(&mut self) -> Option<T> {
-        if let Some(rv) = self.lists.last_mut().and_then(|l| l.pop()) {
-            self.len -= 1;
-            let len = self.lists.len();
-            self.contract(len);
-            Some(rv)
-        } else {
-            None
-        }
+        self.chunks.bump_version();
+        self.chunks.pop_last()
     }
 
     pub fn len(&self) -> usize {
-        self.len
+        self.chunks.len
+    }
+
+    /// A counter that increases whenever the elements may have changed, including when a
+    /// mutable reference or view is handed out. See `SortedList::version`.
+    pub fn version(&self) -> u64 {
+        self.chunks.version
     }
 
     pub fn is_empty(&self) -> bool {
-        self.len == 0
+        self.chunks.len == 0
     }
 
-    pub fn iter(&self) -> Iter<T> {
-        let mut outer = self.lists.iter();
-        let inner = outer.next().unwrap().iter();
-        Iter { outer, inner }
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.chunks.iter()
     }
 
-    #[inline]
-    fn indices(&self, mut i: usize) -> (usize, usize) {
-        let mut outer = 0;
+    /// Removes every element, freeing the sublists.
+    pub fn clear(&mut self) {
+        self.chunks.bump_version();
+        self.chunks.clear(false);
+    }
 
-        // biases towards the earlier list.
-        while i > self.lists[outer].len() {
-            i -= self.lists[outer].len();
-            outer += 1;
+    /// Removes every element, keeping the sublists' allocations to be reused as the list grows
+    /// again. See `SortedList::clear_keep_capacity`.
+    pub fn clear_keep_capacity(&mut self) {
+        self.chunks.bump_version();
+        self.chunks.clear(true);
+    }
+
+    /// Sets every element in a range of indices to clones of `value`.
+    ///
+    /// Each sublist's part of the range is filled with `slice::fill`. Panics if the range is out
+    /// of bounds.
+    pub fn fill<R: RangeBounds<usize>>(&mut self, range: R, value: T)
+    where
+        T: Clone,
+    {
+        self.chunks.bump_version();
+        let range = self.chunks.index_range(range);
+        for slice in self.chunks.slices_mut(range) {
+            slice.fill(value.clone());
+        }
+    }
+
+    /// Sets every element in a range of indices to the values returned by calling `f`, in order.
+    /// Panics if the range is out of bounds.
+    pub fn fill_with<R: RangeBounds<usize>, F: FnMut() -> T>(&mut self, range: R, mut f: F) {
+        self.chunks.bump_version();
+        let range = self.chunks.index_range(range);
+        for slice in self.chunks.slices_mut(range) {
+            slice.fill_with(&mut f);
+        }
+    }
+
+    /// Overwrites a range of indices with the elements of `src`, copying into each sublist's
+    /// part of the range with `slice::copy_from_slice`. Panics if the range is out of bounds or
+    /// isn't the same length as `src`.
+    pub fn copy_within_range<R: RangeBounds<usize>>(&mut self, range: R, src: &[T])
+    where
+        T: Copy,
+    {
+        self.view_mut(range).copy_from_slice(src);
+    }
+
+    /// Divides the list into `n` consecutive parts of roughly equal length, for processing in
+    /// parallel. The parts can be joined back together in order with `+`.
+    ///
+    /// Sublists are moved whole rather than copied, so the lengths are only as even as the
+    /// sublists allow, and some parts are empty if the list has fewer than `n` sublists. Each part
+    /// keeps this list's growth policy. Panics if `n` is zero.
+    pub fn split_into(self, n: usize) -> Vec<UnsortedList<T>> {
+        self.chunks
+            .split_into(n)
+            .into_iter()
+            .map(|chunks| UnsortedList { chunks })
+            .collect()
+    }
+
+    /// Rewrites the sublists to the policy's chunk size after a long run of mixed insertions and
+    /// removals has left them uneven, releasing any spare capacity.
+    ///
+    /// Takes time proportional to the length. `fragmentation` gives a hint for when it is worth
+    /// it.
+    pub fn rebalance(&mut self) {
+        self.chunks.rebalance();
+    }
+
+    /// The fraction of sublists that `rebalance` would remove, from zero when they are all as
+    /// full as the growth policy allows to just under one.
+    pub fn fragmentation(&self) -> f64 {
+        self.chunks.fragmentation()
+    }
+
+    /// The number of elements the sublists have room for. See `SortedList::capacity`.
+    pub fn capacity(&self) -> usize {
+        self.chunks.capacity()
+    }
+
+    /// Trims every sublist and frees any spare ones, as `SortedList::shrink_to_fit` does.
+    pub fn shrink_to_fit(&mut self) {
+        self.chunks.shrink_to_fit();
+    }
+
+    /// Removes the elements at the given indices, returning them in index order. The indices
+    /// may be in any order, and repeats are ignored.
+    ///
+    /// All of the removals are made in a single pass over the sublists, which are then
+    /// rebalanced once, so this is much faster than removing the elements one at a time. Panics if
+    /// any index is out of bounds.
+    pub fn remove_indices<I: IntoIterator<Item = usize>>(&mut self, indices: I) -> Vec<T> {
+        self.chunks.bump_version();
+        self.chunks.remove_positions(indices.into_iter().collect())
+    }
+
+    /// Sorts the list with a comparator, keeping equal elements in their original order.
+    ///
+    /// Each sublist is sorted on its own, while it is small enough to be cache friendly, and the
+    /// sorted sublists are then merged into new, evenly sized ones. Each old sublist is freed once
+    /// it has been merged, but the merge can still need up to `len` elements of extra memory. The
+    /// list stays an `UnsortedList`, so later changes can put it out of order again.
+    pub fn sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, mut compare: F) {
+        self.chunks.bump_version();
+        let mut runs = std::mem::take(&mut self.chunks.lists);
+        for list in &mut runs {
+            list.sort_by(&mut compare);
+        }
+        self.chunks.fill_merged(runs, compare);
+    }
+
+    /// Sorts the list with a comparator, without preserving the order of equal elements. The
+    /// sublists are sorted and merged as in `sort_by`.
+    pub fn sort_unstable_by<F: FnMut(&T, &T) -> Ordering>(&mut self, mut compare: F) {
+        self.chunks.bump_version();
+        let mut runs = std::mem::take(&mut self.chunks.lists);
+        for list in &mut runs {
+            list.sort_unstable_by(&mut compare);
+        }
+        self.chunks.fill_merged(runs, compare);
+    }
+
+    /// Shuffles the elements into a uniformly random order, keeping the sublist sizes balanced.
+    ///
+    /// Requires the `rand` feature.
+    #[cfg(feature = "rand")]
+    pub fn shuffle<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.chunks.bump_version();
+        let mut vals = Vec::with_capacity(self.len());
+        for mut list in std::mem::take(&mut self.chunks.lists) {
+            vals.append(&mut list);
         }
-        (outer, i)
+        vals.shuffle(rng);
+        self.chunks.fill(vals);
+    }
+
+    /// Picks `k` distinct positions uniformly at random and returns their elements, or every
+    /// element if there are fewer than `k`.
+    ///
+    /// The elements are returned in list order. Requires the `rand` feature.
+    #[cfg(feature = "rand")]
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R, k: usize) -> Vec<&T> {
+        let mut positions = index::sample(rng, self.len(), k.min(self.len())).into_vec();
+        positions.sort_unstable();
+        let mut positions = positions.into_iter().peekable();
+        self.iter()
+            .enumerate()
+            .filter(|&(i, _)| positions.next_if_eq(&i).is_some())
+            .map(|(_, x)| x)
+            .collect()
+    }
+
+    /// Keeps only the elements for which `pred` returns true, in their original order.
+    ///
+    /// The sublists are filtered in parallel on the rayon thread pool, and then rebalanced once.
+    /// Requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn par_retain<F>(&mut self, pred: F)
+    where
+        T: Send,
+        F: Fn(&T) -> bool + Sync,
+    {
+        self.chunks.bump_version();
+        self.chunks
+            .lists
+            .par_iter_mut()
+            .for_each(|list| list.retain(&pred));
+        self.chunks.normalize();
+    }
+
+    /// Calls `f` on each sublist in parallel on the rayon thread pool.
+    ///
+    /// Requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn par_for_each_chunk<F>(&mut self, f: F)
+    where
+        T: Send,
+        F: Fn(&mut [T]) + Sync,
+    {
+        self.chunks.bump_version();
+        self.chunks.lists.par_iter_mut().for_each(|list| f(list));
+    }
+
+    /// The sublists in order, as slices.
+    #[cfg(feature = "bytes")]
+    pub(crate) fn as_slices(&self) -> impl Iterator<Item = &[T]> {
+        self.chunks.lists.iter().map(Vec::as_slice)
     }
 }
 
+impl<T: Ord> UnsortedList<T> {
+    /// Sorts the list in ascending order. See `sort_by`.
+    pub fn sort(&mut self) {
+        self.sort_by(T::cmp);
+    }
+
+    /// Whether the elements are already in ascending order, checked in one pass over the
+    /// sublists.
+    pub fn is_sorted(&self) -> bool {
+        let lists = &self.chunks.lists;
+        lists.iter().all(|list| list.is_sorted())
+            && lists
+                .windows(2)
+                .all(|pair| match (pair[0].last(), pair[1].first()) {
+                    (Some(last), Some(first)) => last <= first,
+                    _ => true,
+                })
+    }
+
+    /// Removes every element, yielding them in ascending order.
+    ///
+    /// Each sublist is sorted in place and the iterator merges them, so consuming only the
+    /// smallest few elements doesn't pay for a full sort. The list is left empty, and any
+    /// elements that haven't been yielded when the iterator is dropped are dropped with it.
+    pub fn drain_sorted(&mut self) -> DrainSorted<'_, T> {
+        self.chunks.bump_version();
+        let heap = self
+            .chunks
+            .take()
+            .lists
+            .into_iter()
+            .filter_map(|mut list| {
+                list.sort_unstable();
+                let mut rest = list.into_iter();
+                rest.next().map(|val| DrainHead { val, rest })
+            })
+            .collect();
+        DrainSorted {
+            heap,
+            list: PhantomData,
+        }
+    }
+}
+
+/// The iterator returned by `UnsortedList::drain_sorted`.
+#[derive(Debug)]
+pub struct DrainSorted<'a, T: Ord + 'a> {
+    heap: BinaryHeap<DrainHead<T>>,
+    list: PhantomData<&'a mut UnsortedList<T>>,
+}
+
+/// The smallest remaining element of a sorted sublist, and the rest of that sublist.
+#[derive(Debug)]
+struct DrainHead<T> {
+    val: T,
+    rest: std::vec::IntoIter<T>,
+}
+
+// Ordered in reverse, so that `BinaryHeap` pops the smallest head first.
+impl<T: Ord> Ord for DrainHead<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.val.cmp(&self.val)
+    }
+}
+
+impl<T: Ord> PartialOrd for DrainHead<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> PartialEq for DrainHead<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.val == other.val
+    }
+}
+
+impl<T: Ord> Eq for DrainHead<T> {}
+
+impl<'a, T: Ord> Iterator for DrainSorted<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let mut head = self.heap.peek_mut()?;
+        match head.rest.next() {
+            Some(next) => Some(std::mem::replace(&mut head.val, next)),
+            None => Some(PeekMut::pop(head).val),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.heap.iter().map(|head| head.rest.len() + 1).sum();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: Ord> ExactSizeIterator for DrainSorted<'a, T> {}
+
+impl<'a, T: Ord> FusedIterator for DrainSorted<'a, T> {}
+
 impl<T: PartialEq> UnsortedList<T> {
     pub fn contains(&self, val: &T) -> bool {
-        debug_assert!(!self.lists.is_empty());
+        self.chunks.lists.iter().any(|list| list.contains(val))
+    }
 
-        self.lists.iter().any(|list| list.contains(val))
+    /// Whether the list begins with the elements of `prefix`, compared a sublist at a time.
+    pub fn starts_with(&self, prefix: &[T]) -> bool {
+        prefix.len() <= self.len() && Self::slices_start_with(self.chunks.lists.iter(), prefix)
+    }
+
+    /// Whether the list ends with the elements of `suffix`, compared a sublist at a time.
+    pub fn ends_with(&self, suffix: &[T]) -> bool {
+        if suffix.len() > self.len() {
+            return false;
+        }
+        let mut rest = suffix;
+        for list in self.chunks.lists.iter().rev() {
+            let n = list.len().min(rest.len());
+            let (head, tail) = rest.split_at(rest.len() - n);
+            if list[list.len() - n..] != *tail {
+                return false;
+            }
+            rest = head;
+        }
+        true
+    }
+
+    /// Whether the list holds exactly the elements of `slice`, in order.
+    pub fn eq_slice(&self, slice: &[T]) -> bool {
+        slice.len() == self.len() && Self::slices_start_with(self.chunks.lists.iter(), slice)
+    }
+
+    fn slices_start_with<'a, I>(lists: I, prefix: &[T]) -> bool
+    where
+        I: Iterator<Item = &'a Vec<T>>,
+        T: 'a,
+    {
+        let mut rest = prefix;
+        for list in lists {
+            let n = list.len().min(rest.len());
+            let (head, tail) = rest.split_at(n);
+            if list[..n] != *head {
+                return false;
+            }
+            rest = tail;
+        }
+        rest.is_empty()
     }
 }
 
@@ -195,7 +511,7 @@ impl<T: Ord> IntoIterator for UnsortedList<T> {
 
     fn into_iter(self) -> IntoIter<T> {
         IntoIter {
-            outer: self.lists.into_iter(),
+            outer: self.chunks.lists.into_iter(),
             inner: Vec::new().into_iter(),
         }
     }
@@ -207,35 +523,102 @@ impl<T: Ord> Default for UnsortedList<T> {
     }
 }
 
-/// Does a probably O(n^2) collection from an iterator -- but it's an iterator, not a
-/// collection we're sorting, so what do you expect?
+/// Collects an iterator by filling sublists of the growth policy's chunk size directly from it.
 ///
-/// Actually may not be that bad based on the performance analysis that's todo
-impl<'a, T: Ord> FromIterator<T> for UnsortedList<T> {
+/// As with `Vec`, an iterator of `Result`s can be collected into a `Result<UnsortedList<T>, E>`,
+/// which stops at the first error.
+impl<T: Ord> FromIterator<T> for UnsortedList<T> {
     fn from_iter<F>(iter: F) -> Self
     where
         F: IntoIterator<Item = T>,
     {
+        // Fused so that nothing is pulled after the first `None`, which `Result` collection
+        // relies on to stop at the first error.
+        let mut iter = iter.into_iter().fuse();
         let mut list = Self::default();
-        for x in iter {
-            list.push(x);
+        let size = list.chunks.policy().chunk_size(iter.size_hint().0).max(1);
+        list.chunks.lists.clear();
+        list.chunks.lists.reserve(iter.size_hint().0 / size + 1);
+        loop {
+            let chunk: Vec<T> = iter.by_ref().take(size).collect();
+            if chunk.is_empty() {
+                break;
+            }
+            list.chunks.len += chunk.len();
+            list.chunks.lists.push(chunk);
+        }
+        if list.chunks.lists.is_empty() {
+            list.chunks.lists.push(Vec::new());
         }
         list
     }
 }
 
+/// Collects by cloning the referenced values.
+impl<'a, T: 'a + Ord + Clone> FromIterator<&'a T> for UnsortedList<T> {
+    fn from_iter<F>(iter: F) -> Self
+    where
+        F: IntoIterator<Item = &'a T>,
+    {
+        iter.into_iter().cloned().collect()
+    }
+}
+
+/// Pushes each value onto the end in turn.
+impl<T: Ord> Extend<T> for UnsortedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
 impl<T: Ord> Index<usize> for UnsortedList<T> {
     type Output = T;
     fn index(&self, i: usize) -> &T {
-        let (i, j) = self.indices(i);
-        &self.lists[i][j]
+        let (i, j) = self.chunks.expect_position_indices(i);
+        &self.chunks.lists[i][j]
     }
 }
 
 impl<T: Ord> IndexMut<usize> for UnsortedList<T> {
     fn index_mut(&mut self, i: usize) -> &mut T {
-        let (i, j) = self.indices(i);
-        &mut self.lists[i][j]
+        self.chunks.bump_version();
+        let (i, j) = self.chunks.expect_position_indices(i);
+        &mut self.chunks.lists[i][j]
+    }
+}
+
+/// Concatenates two lists by moving the sublists of `other` onto the end, without copying
+/// their elements. If `other` has a different growth policy, any of its sublists that are too
+/// long for this list's policy are split.
+impl<T> AddAssign for UnsortedList<T> {
+    fn add_assign(&mut self, other: Self) {
+        self.chunks.bump_version();
+        if self.is_empty() {
+            self.chunks.lists.clear();
+        }
+        self.chunks.lists.extend(
+            other
+                .chunks
+                .lists
+                .into_iter()
+                .filter(|list| !list.is_empty()),
+        );
+        if self.chunks.lists.is_empty() {
+            self.chunks.lists.push(Vec::new());
+        }
+        self.chunks.len += other.chunks.len;
+        self.chunks.split_oversized();
+    }
+}
+
+impl<T> Add for UnsortedList<T> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
     }
 }
 
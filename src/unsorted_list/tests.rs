@@ -1,4 +1,6 @@
 use super::UnsortedList;
+use chunked::ChunkedStorage;
+use LoadFactor;
 #[test]
 fn empty() {
     let mut list: UnsortedList<i32> = UnsortedList::default();
@@ -26,16 +28,45 @@ fn index() {
     assert_eq!(list.pop(), Some(10));
 }
 
+#[test]
+fn index_at_sublist_boundaries() {
+    let mut collected: UnsortedList<u32> = (0..3000).collect();
+    let mut pushed = UnsortedList::with_growth_policy(LoadFactor(4));
+    for x in 0..50 {
+        pushed.push(x);
+    }
+    for list in &mut [&mut collected, &mut pushed] {
+        assert!(list.chunks.lists.len() > 1);
+        let mut start = 0;
+        for k in 0..list.chunks.lists.len() {
+            let len = list.chunks.lists[k].len();
+            for i in [start, start + len - 1] {
+                assert_eq!(i as u32, list[i]);
+                list[i] += 0;
+            }
+            start += len;
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "index 3000 out of range for list of length 3000")]
+fn index_out_of_range() {
+    let list: UnsortedList<u32> = (0..3000).collect();
+    let _ = list[3000];
+}
+
 #[test]
 fn test_actual_contract() {
     let mut list = UnsortedList::<i32> {
-        lists: vec![vec![-6, -5, -3], vec![1, 2, 3, 4, 5], vec![99, 100]],
-        load_factor: 2,
-        len: 10,
+        chunks: ChunkedStorage::from_lists(
+            vec![vec![-6, -5, -3], vec![1, 2, 3, 4, 5], vec![99, 100]],
+            Box::new(LoadFactor(2)),
+        ),
     };
-    list.unchecked_contract(1);
+    list.chunks.unchecked_contract(1);
     assert_eq!(
-        list.lists,
+        list.chunks.lists,
         vec![vec![-6, -5, -3], vec![1, 2, 3, 4, 5, 99, 100]]
     );
 }
@@ -76,11 +107,11 @@ quickcheck! {
     }
 
     fn from_iter(list: Vec<u32>) -> bool {
-    let from_iter: UnsortedList<u32> = list.iter().map(|x| x.clone()).collect();
+    let from_iter: UnsortedList<u32> = list.iter().copied().collect();
     let from_collection = {
         let mut collection = UnsortedList::default();
         for x in list.iter() {
-            collection.push(x.clone());
+            collection.push(*x);
         }
         collection
     };
@@ -89,3 +120,427 @@ quickcheck! {
         && list.iter().eq(from_collection.iter())
     }
 }
+
+#[test]
+fn from_iter_chunks() {
+    let list: UnsortedList<u32> = (0..2500).collect();
+    assert_eq!(2500, list.len());
+    assert_eq!(
+        vec![1000, 1000, 500],
+        list.chunks.lists.iter().map(Vec::len).collect::<Vec<_>>()
+    );
+    assert!(list.iter().cloned().eq(0..2500));
+
+    let empty: UnsortedList<u32> = None::<u32>.into_iter().collect();
+    assert_eq!(1, empty.chunks.lists.len());
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn push_and_pop_many() {
+    let mut list = UnsortedList::default();
+    for i in 0..5000 {
+        list.push(i);
+    }
+    assert!(list.chunks.lists.len() > 1);
+    assert!(list
+        .chunks
+        .lists
+        .iter()
+        .all(|l| !list.chunks.should_split(l.len())));
+    for i in (0..5000).rev() {
+        assert_eq!(Some(i), list.pop());
+    }
+    assert_eq!(None, list.pop());
+    assert!(list.is_empty());
+}
+
+#[test]
+fn concatenate() {
+    let a: UnsortedList<u32> = (0..2500).collect();
+    let b: UnsortedList<u32> = (2500..3000).collect();
+    let mut joined = a + b;
+    assert_eq!(3000, joined.len());
+    assert!(joined.iter().cloned().eq(0..3000));
+
+    joined += UnsortedList::new();
+    assert_eq!(3000, joined.len());
+    assert_eq!(4, joined.chunks.lists.len());
+
+    let mut empty = UnsortedList::new();
+    empty += joined;
+    assert_eq!(4, empty.chunks.lists.len());
+    assert_eq!(Some(&2999), empty.last());
+    assert!(empty.chunks.check());
+
+    // Sublists from a list with a looser policy are split to suit this one.
+    let mut loose = UnsortedList::with_growth_policy(LoadFactor(10_000));
+    loose.extend(3000..8000);
+    assert_eq!(1, loose.chunks.lists.len());
+    empty += loose;
+    assert!(empty.chunks.check());
+    assert!(empty.iter().cloned().eq(0..8000));
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn shuffle_and_sample() {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut list: UnsortedList<u32> = (0..3000).collect();
+    list.shuffle(&mut rng);
+    assert_eq!(3000, list.len());
+    assert!(!list.iter().cloned().eq(0..3000));
+    let mut vals: Vec<u32> = list.iter().cloned().collect();
+    vals.sort();
+    assert!(vals.into_iter().eq(0..3000));
+    assert!(list.chunks.check());
+
+    let sample = list.sample(&mut rng, 10);
+    assert_eq!(10, sample.len());
+    let mut distinct: Vec<u32> = sample.iter().map(|x| **x).collect();
+    distinct.sort();
+    distinct.dedup();
+    assert_eq!(10, distinct.len());
+    assert_eq!(3000, list.sample(&mut rng, 5000).len());
+}
+
+quickcheck! {
+    fn prop_sort(vals: Vec<(u8, u16)>) -> bool {
+        let mut list = UnsortedList::with_growth_policy(LoadFactor(4));
+        for &x in &vals {
+            list.push(x);
+        }
+        let mut expected = vals.clone();
+        expected.sort_by_key(|x| x.0);
+        list.sort_by(|a, b| a.0.cmp(&b.0));
+        let stable = list.iter().eq(expected.iter()) && list.chunks.check();
+
+        expected.sort();
+        list.sort_unstable_by(|a, b| b.cmp(a));
+        let descending = list.iter().eq(expected.iter().rev()) && list.chunks.check();
+        list.sort();
+        stable && descending && list.iter().eq(expected.iter()) && list.chunks.check()
+    }
+}
+
+static EMPTY: UnsortedList<u32> = UnsortedList::new();
+
+#[test]
+fn lazy_first_sublist() {
+    assert!(EMPTY.is_empty());
+    assert_eq!(None, EMPTY.first());
+    assert_eq!(None, EMPTY.iter().next());
+    assert!(!EMPTY.contains(&0));
+
+    let mut list = UnsortedList::new();
+    assert_eq!(None, list.pop());
+    assert_eq!(None, list.pop_first());
+    list.insert(0, 1);
+    list.push(2);
+    assert!(list.iter().eq([1, 2].iter()));
+}
+
+quickcheck! {
+    fn prop_fill(len: u8, a: u8, b: u8) -> bool {
+        let (len, a, b) = (usize::from(len), usize::from(a), usize::from(b));
+        let (start, end) = (a.min(b).min(len), a.max(b).min(len));
+        let mut list = UnsortedList::with_growth_policy(LoadFactor(4));
+        for x in 0..len {
+            list.push(x);
+        }
+        let mut expected: Vec<usize> = (0..len).collect();
+
+        list.fill(start..end, 1000);
+        expected[start..end].fill(1000);
+        let filled = list.iter().eq(expected.iter());
+
+        let mut next = 0;
+        list.fill_with(start.., || { next += 1; next });
+        let mut next = 0;
+        expected[start..].fill_with(|| { next += 1; next });
+        filled && list.iter().eq(expected.iter())
+    }
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn fill_out_of_range() {
+    let mut list: UnsortedList<u32> = (0..10).collect();
+    list.fill(5..=10, 0);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_retain_and_for_each_chunk() {
+    let mut list: UnsortedList<u32> = (0..10000).rev().collect();
+    list.par_retain(|x| x % 3 == 0);
+    assert!(list
+        .iter()
+        .cloned()
+        .eq((0..10000).rev().filter(|x| x % 3 == 0)));
+    assert!(list.chunks.check());
+
+    list.par_for_each_chunk(|chunk| chunk.reverse());
+    assert_eq!(3334, list.len());
+    assert!(!list.iter().is_sorted());
+}
+
+quickcheck! {
+    fn prop_is_sorted(vals: Vec<u8>) -> bool {
+        let mut list = UnsortedList::with_growth_policy(LoadFactor(4));
+        for &x in &vals {
+            list.push(x);
+        }
+        let unsorted = list.is_sorted() == vals.is_sorted();
+        list.sort();
+        unsorted && list.is_sorted()
+    }
+}
+
+quickcheck! {
+    fn prop_split_into(len: u16, n: u8) -> bool {
+        let n = usize::from(n % 8) + 1;
+        let mut list = UnsortedList::with_growth_policy(LoadFactor(4));
+        for x in 0..len {
+            list.push(x);
+        }
+        let chunk_count = list.chunks.lists.len();
+        let parts = list.split_into(n);
+        let lens: Vec<usize> = parts.iter().map(UnsortedList::len).collect();
+        let even = chunk_count < n
+            || lens.iter().all(|&l| l.abs_diff(usize::from(len) / n) <= 8);
+
+        let joined = parts.into_iter().fold(UnsortedList::new(), |a, b| a + b);
+        lens.len() == n && even && joined.iter().cloned().eq(0..len)
+    }
+}
+
+#[test]
+fn rebalance() {
+    let mut list = UnsortedList::with_growth_policy(LoadFactor(4));
+    for x in 0..1000 {
+        list.insert(x / 2, x);
+    }
+    let before: Vec<usize> = list.iter().cloned().collect();
+    list.rebalance();
+    assert_eq!(0.0, list.fragmentation());
+    assert!(list.chunks.check());
+    assert!(list.iter().eq(before.iter()));
+}
+
+quickcheck! {
+    fn prop_remove_indices(len: u16, indices: Vec<u16>) -> bool {
+        let len = usize::from(len % 500);
+        let indices: Vec<usize> = indices.into_iter().map(usize::from).filter(|&i| i < len).collect();
+        let mut list = UnsortedList::with_growth_policy(LoadFactor(4));
+        for x in 0..len {
+            list.push(x);
+        }
+        let removed = list.remove_indices(indices.iter().cloned());
+
+        let mut expected_removed = indices.clone();
+        expected_removed.sort();
+        expected_removed.dedup();
+        let kept = (0..len).filter(|x| expected_removed.binary_search(x).is_err());
+        removed == expected_removed && list.iter().cloned().eq(kept) && list.chunks.check()
+    }
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn remove_indices_out_of_range() {
+    let mut list: UnsortedList<u32> = (0..10).collect();
+    list.remove_indices(vec![3, 10]);
+}
+
+#[test]
+fn zero_sized_elements() {
+    let mut list = UnsortedList::with_growth_policy(LoadFactor(4));
+    for i in 0..100 {
+        list.insert(i / 2, ());
+    }
+    assert_eq!(100, list.len());
+    assert!(list.chunks.check());
+    assert_eq!(100, list.iter().count());
+    assert_eq!((), list[99]);
+    list.fill(10..90, ());
+    assert_eq!(80, list.remove_indices(10..90).len());
+    assert!(list.is_sorted());
+    assert_eq!(
+        20,
+        list.split_into(3)
+            .into_iter()
+            .map(|l| l.len())
+            .sum::<usize>()
+    );
+
+    let mut collected: UnsortedList<()> = std::iter::repeat_n((), 2500).collect();
+    assert_eq!(2500, collected.len());
+    assert!(collected.chunks.lists.len() > 1);
+    let mut start = 0;
+    for len in collected
+        .chunks
+        .lists
+        .iter()
+        .map(Vec::len)
+        .collect::<Vec<_>>()
+    {
+        assert_eq!((), collected[start]);
+        assert_eq!((), collected[start + len - 1]);
+        collected[start] = ();
+        start += len;
+    }
+    assert_eq!((), collected[1000]);
+    collected.sort();
+    while collected.pop().is_some() {}
+    assert!(collected.is_empty());
+}
+
+#[test]
+fn collect_results() {
+    let ok: Result<UnsortedList<u32>, String> = (0..3000).map(Ok).collect();
+    assert!(ok.unwrap().iter().cloned().eq(0..3000));
+
+    let mut pulled = 0;
+    let err: Result<UnsortedList<u32>, u32> = (0..3000)
+        .inspect(|_| pulled += 1)
+        .map(|x| if x == 10 { Err(x) } else { Ok(x) })
+        .collect();
+    assert_eq!(Err(10), err.map(|list| list.len() as u32));
+    assert_eq!(11, pulled);
+}
+
+#[test]
+fn collect_references() {
+    let vals: Vec<String> = (0..3000).map(|x| x.to_string()).collect();
+    let list: UnsortedList<String> = vals.iter().collect();
+    assert!(list.iter().eq(vals.iter()));
+}
+
+#[test]
+fn with_load_factor() {
+    let mut list = UnsortedList::with_load_factor(16);
+    list.extend(0..1000);
+    assert!(list.chunks.check());
+    assert!(list.chunks.lists.iter().all(|l| l.len() < 32));
+}
+
+#[test]
+fn extend() {
+    let mut list = UnsortedList::with_growth_policy(LoadFactor(4));
+    list.extend((0..100).rev());
+    list.extend(vec![7, 8]);
+    assert!(list.iter().cloned().eq((0..100).rev().chain(vec![7, 8])));
+    assert!(list.chunks.check());
+}
+
+quickcheck! {
+    fn prop_slice_comparisons(vals: Vec<u8>, split: usize) -> bool {
+        let vals: Vec<u8> = vals.into_iter().map(|x| x % 3).collect();
+        let mut list = UnsortedList::with_growth_policy(LoadFactor(4));
+        for &x in &vals {
+            list.push(x);
+        }
+        let split = split % (vals.len() + 1);
+        let (head, tail) = vals.split_at(split);
+        // The same elements with one changed, so that comparisons around it fail.
+        let mut shifted = vals.clone();
+        if let Some(x) = shifted.get_mut(split / 2) {
+            *x += 1;
+        }
+        list.starts_with(head)
+            && list.ends_with(tail)
+            && list.eq_slice(&vals)
+            && list.starts_with(&shifted[..split]) == vals.starts_with(&shifted[..split])
+            && list.ends_with(&shifted[split..]) == vals.ends_with(&shifted[split..])
+            && list.eq_slice(&shifted) == (vals == shifted)
+            && list.eq_slice(head) == (split == vals.len())
+            && !list.starts_with(&[vals.as_slice(), &[0]].concat())
+    }
+}
+
+quickcheck! {
+    fn prop_drain_sorted(vals: Vec<i16>, taken: usize) -> bool {
+        let mut list = UnsortedList::with_growth_policy(LoadFactor(4));
+        for &x in &vals {
+            list.push(x);
+        }
+        let mut sorted = vals.clone();
+        sorted.sort();
+        let taken = taken % (vals.len() + 1);
+
+        let mut drain = list.drain_sorted();
+        let len_ok = drain.len() == vals.len();
+        let drained: Vec<i16> = drain.by_ref().take(taken).collect();
+        let rest_len = drain.len();
+        drop(drain);
+        let emptied = list.is_empty();
+        list.push(1);
+        len_ok && drained == sorted[..taken] && rest_len == vals.len() - taken && emptied
+            && list.len() == 1
+    }
+}
+
+quickcheck! {
+    fn prop_copy_within_range(len: u8, start: u8, src: Vec<u8>) -> bool {
+        let len = usize::from(len);
+        let start = usize::from(start) % (len + 1);
+        let src = &src[..src.len().min(len - start)];
+        let mut list = UnsortedList::with_growth_policy(LoadFactor(4));
+        for x in 0..len {
+            list.push(x as u8);
+        }
+        let mut expected: Vec<u8> = (0..len).map(|x| x as u8).collect();
+        list.copy_within_range(start..start + src.len(), src);
+        expected[start..start + src.len()].copy_from_slice(src);
+        list.eq_slice(&expected)
+    }
+}
+
+#[test]
+#[should_panic(expected = "length")]
+fn copy_within_range_length_mismatch() {
+    let mut list: UnsortedList<u32> = (0..10).collect();
+    list.copy_within_range(2..5, &[1, 2]);
+}
+
+#[test]
+fn version() {
+    let mut list = UnsortedList::new();
+    let start = list.version();
+    list.push(3);
+    list.insert(0, 4);
+    let pushed = list.version();
+    assert!(pushed > start);
+    assert!(list.contains(&3) && list.starts_with(&[4]));
+    list.rebalance();
+    assert_eq!(pushed, list.version());
+    list[1] += 1;
+    assert!(list.version() > pushed);
+    let indexed = list.version();
+    list.view_mut(..1);
+    assert!(list.version() > indexed);
+    let viewed = list.version();
+    list.sort();
+    assert!(list.version() > viewed);
+}
+
+#[test]
+fn clear() {
+    let mut list = UnsortedList::with_growth_policy(LoadFactor(4));
+    for x in 0..100 {
+        list.push(x);
+    }
+    list.clear_keep_capacity();
+    assert!(list.is_empty() && list.chunks.check());
+    for x in 0..100 {
+        list.push(x);
+    }
+    assert!(list.iter().cloned().eq(0..100) && list.chunks.check());
+    list.clear();
+    assert!(list.is_empty() && list.chunks.check());
+    assert_eq!(None, list.pop());
+}
@@ -0,0 +1,241 @@
+//! Mutable access to a range of an `UnsortedList`.
+//!
+//! A `ListSliceMut` borrows a contiguous range of positions and indexes from the start of that
+//! range, so code written for a sub-region can't reach outside it. Bulk operations work through
+//! the parts of the sublists the range covers, a slice at a time.
+//!
+//! # Example usage
+//! ```
+//! use sorted_collections::UnsortedList;
+//! let mut list: UnsortedList<u32> = (0..10).collect();
+//! {
+//!     let mut view = list.view_mut(2..5);
+//!     view.copy_from_slice(&[7, 8, 9]);
+//!     view.swap(0, 2);
+//!     view[1] += 10;
+//! }
+//! assert_eq!(vec![0, 1, 9, 18, 7, 5], list.iter().cloned().take(6).collect::<Vec<_>>());
+//! ```
+
+use super::UnsortedList;
+use chunked::ChunkedStorage;
+use std::ops::{Index, IndexMut, Range, RangeBounds};
+
+/// A mutable view of a contiguous range of an `UnsortedList`.
+///
+/// Elements can be changed, but not added or removed.
+#[derive(Debug)]
+pub struct ListSliceMut<'a, T: 'a> {
+    chunks: &'a mut ChunkedStorage<T>,
+    range: Range<usize>,
+}
+
+impl<T> UnsortedList<T> {
+    /// Borrows a range of positions for modification. Panics if the range is out of bounds.
+    pub fn view_mut<R: RangeBounds<usize>>(&mut self, range: R) -> ListSliceMut<'_, T> {
+        let range = self.chunks.index_range(range);
+        self.chunks.bump_version();
+        ListSliceMut {
+            chunks: &mut self.chunks,
+            range,
+        }
+    }
+}
+
+impl<'a, T> ListSliceMut<'a, T> {
+    pub fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+
+    /// The element at position `i` of the view, or `None` if it is outside the view.
+    pub fn get(&self, i: usize) -> Option<&T> {
+        let (i, j) = self.indices(i)?;
+        Some(&self.chunks.lists[i][j])
+    }
+
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        let (i, j) = self.indices(i)?;
+        Some(&mut self.chunks.lists[i][j])
+    }
+
+    /// Swaps two elements of the view. Panics if either is outside it.
+    pub fn swap(&mut self, a: usize, b: usize) {
+        let ((ai, aj), (bi, bj)) = (self.expect(a), self.expect(b));
+        if ai == bi {
+            self.chunks.lists[ai].swap(aj, bj);
+        } else {
+            let (low, high) = ((ai, aj).min((bi, bj)), (ai, aj).max((bi, bj)));
+            let (front, back) = self.chunks.lists.split_at_mut(high.0);
+            std::mem::swap(&mut front[low.0][low.1], &mut back[0][high.1]);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        let start = self
+            .chunks
+            .position_indices(self.range.start)
+            .unwrap_or_else(|| self.chunks.end_indices());
+        self.chunks.iter_from_indices(start).take(self.len())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> + '_ {
+        self.slices_mut().flat_map(|slice| slice.iter_mut())
+    }
+
+    /// The parts of the sublists that make up the view, in order. Empty parts are skipped.
+    pub fn slices_mut(&mut self) -> impl Iterator<Item = &mut [T]> + '_ {
+        self.chunks.slices_mut(self.range.clone())
+    }
+
+    /// Sets every element of the view to clones of `value`.
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        for slice in self.slices_mut() {
+            slice.fill(value.clone());
+        }
+    }
+
+    /// Sets every element of the view to the values returned by calling `f`, in order.
+    pub fn fill_with<F: FnMut() -> T>(&mut self, mut f: F) {
+        for slice in self.slices_mut() {
+            slice.fill_with(&mut f);
+        }
+    }
+
+    /// Copies `src` over the view. Panics if they have different lengths.
+    pub fn copy_from_slice(&mut self, src: &[T])
+    where
+        T: Copy,
+    {
+        let mut rest = self.check_len(src);
+        for slice in self.slices_mut() {
+            let (head, tail) = rest.split_at(slice.len());
+            slice.copy_from_slice(head);
+            rest = tail;
+        }
+    }
+
+    /// Clones `src` over the view. Panics if they have different lengths.
+    pub fn clone_from_slice(&mut self, src: &[T])
+    where
+        T: Clone,
+    {
+        let mut rest = self.check_len(src);
+        for slice in self.slices_mut() {
+            let (head, tail) = rest.split_at(slice.len());
+            slice.clone_from_slice(head);
+            rest = tail;
+        }
+    }
+
+    fn check_len<'b>(&self, src: &'b [T]) -> &'b [T] {
+        assert_eq!(
+            self.len(),
+            src.len(),
+            "source slice length doesn't match the view length"
+        );
+        src
+    }
+
+    /// The (sublist, offset) position of element `i` of the view.
+    fn indices(&self, i: usize) -> Option<(usize, usize)> {
+        if i < self.len() {
+            self.chunks.position_indices(self.range.start + i)
+        } else {
+            None
+        }
+    }
+
+    fn expect(&self, i: usize) -> (usize, usize) {
+        match self.indices(i) {
+            Some(indices) => indices,
+            None => panic!("index {} out of range for view of length {}", i, self.len()),
+        }
+    }
+}
+
+impl<'a, T> Index<usize> for ListSliceMut<'a, T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        let (i, j) = self.expect(i);
+        &self.chunks.lists[i][j]
+    }
+}
+
+impl<'a, T> IndexMut<usize> for ListSliceMut<'a, T> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        let (i, j) = self.expect(i);
+        &mut self.chunks.lists[i][j]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use LoadFactor;
+
+    quickcheck! {
+        fn prop_view_mut(len: u8, a: u8, b: u8) -> bool {
+            let (len, a, b) = (usize::from(len), usize::from(a), usize::from(b));
+            let (start, end) = (a.min(b).min(len), a.max(b).min(len));
+            let mut list = UnsortedList::with_growth_policy(LoadFactor(4));
+            for x in 0..len {
+                list.push(x);
+            }
+            let mut expected: Vec<usize> = (0..len).collect();
+
+            let mut view = list.view_mut(start..end);
+            let n = view.len();
+            let src: Vec<usize> = (0..n).map(|x| x * 100).collect();
+            view.copy_from_slice(&src);
+            expected[start..end].copy_from_slice(&src);
+            if n > 1 {
+                view.swap(0, n - 1);
+                expected.swap(start, end - 1);
+                view[n / 2] += 1;
+                expected[start + n / 2] += 1;
+            }
+            for x in view.iter_mut().step_by(3) {
+                *x += 7;
+            }
+            for x in expected[start..end].iter_mut().step_by(3) {
+                *x += 7;
+            }
+            let viewed = view.iter().eq(expected[start..end].iter())
+                && view.get(n).is_none()
+                && view.slices_mut().map(|s| s.len()).sum::<usize>() == n;
+            viewed && list.iter().eq(expected.iter())
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn index_outside_view() {
+        let mut list: UnsortedList<u32> = (0..10).collect();
+        let view = list.view_mut(2..4);
+        let _ = view[2];
+    }
+
+    #[test]
+    fn fill() {
+        let mut list: UnsortedList<u32> = (0..3000).collect();
+        list.view_mut(500..2500).fill(0);
+        let mut view = list.view_mut(..1000);
+        let mut next = 0;
+        view.fill_with(|| {
+            next += 1;
+            next
+        });
+        view.clone_from_slice(&(0..1000).rev().collect::<Vec<_>>());
+        assert!(list.iter().take(1000).cloned().eq((0..1000).rev()));
+        assert!(list.iter().skip(1000).take(1500).all(|&x| x == 0));
+        assert!(list.iter().skip(2500).cloned().eq(2500..3000));
+    }
+}